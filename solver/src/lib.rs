@@ -0,0 +1,1738 @@
+//! The solver's core: working out clues, filtering and ranking words by them,
+//! and picking openers. Nothing here prints or reads input, so it can be used
+//! from other programs as well as the CLI.
+
+use bytecheck::CheckBytes;
+use colored::{ColoredString, Colorize};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+use rkyv::{Archive, Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+/// Most openers `--approx-opener` tries before settling on the best so far
+pub const ANNEAL_STEPS: usize = 2000;
+
+/// How often `--approx-opener` jumps to any word on the list rather than one a
+/// letter away, so it doesn't get stuck among a few similar words
+pub const ANNEAL_RESTART: f64 = 0.1;
+
+/// With `--penalise-repeats`, the share of its score a word with repeated
+/// letters loses when ranked, while at least `REPEAT_PENALTY_FULL` words remain.
+/// Between that and `REPEAT_PENALTY_NONE` words the penalty shrinks to nothing.
+pub const REPEAT_PENALTY: f64 = 0.5;
+pub const REPEAT_PENALTY_FULL: usize = 100;
+pub const REPEAT_PENALTY_NONE: usize = 10;
+
+/// With `--bigrams`, the most a word's score can grow by for being made of the
+/// commonest letter pairs. It's small, so only words that scored about the same
+/// swap places
+pub const BIGRAM_BONUS: f64 = 0.1;
+
+/// The colours tiles are drawn in. The high contrast palette uses orange for
+/// green and blue for yellow, like the NYT's, for players who find green and
+/// yellow hard to tell apart.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Palette {
+    #[default]
+    Standard,
+    HighContrast,
+}
+
+impl Palette {
+    /// Reads a palette name, like `standard` or `high-contrast`. `colorblind`
+    /// is accepted for the high contrast one too
+    pub fn parse(name: &str) -> Option<Palette> {
+        match name.replace(['-', '_'], "").to_lowercase().as_str() {
+            "standard" => Some(Palette::Standard),
+            "highcontrast" | "colorblind" | "colourblind" => Some(Palette::HighContrast),
+            _ => None,
+        }
+    }
+
+    /// Colours text the way a green tile is drawn
+    pub fn green(self, text: &str) -> ColoredString {
+        match self {
+            Palette::Standard => text.green(),
+            Palette::HighContrast => text.truecolor(245, 121, 58),
+        }
+    }
+
+    /// Colours text the way a yellow tile is drawn
+    pub fn yellow(self, text: &str) -> ColoredString {
+        match self {
+            Palette::Standard => text.yellow(),
+            Palette::HighContrast => text.bright_blue(),
+        }
+    }
+
+    /// Colours text the way a letter that isn't in the word is drawn, which is
+    /// the same in every palette
+    pub fn red(self, text: &str) -> ColoredString {
+        text.red()
+    }
+
+    /// The squares shared for green and yellow tiles
+    pub fn squares(self) -> (char, char) {
+        match self {
+            Palette::Standard => ('🟩', '🟨'),
+            Palette::HighContrast => ('🟧', '🟦'),
+        }
+    }
+}
+
+/// The colour of each tile after a guess
+#[derive(PartialEq, Eq)]
+pub struct GuessResult(pub [Character; 5]);
+
+impl GuessResult {
+    /// Encodes the colours as a base-3 number (green = 2, yellow = 1, red = 0 per
+    /// position), so two results can be compared or bucketed cheaply.
+    pub fn code(&self) -> u8 {
+        self.0.iter().fold(0, |acc, c| {
+            acc * 3
+                + match c {
+                    Character::Green(_) => 2,
+                    Character::Yellow(_) => 1,
+                    Character::Red(_) | Character::Wild | Character::Empty => 0,
+                }
+        })
+    }
+
+    /// The result of guessing `guess` with the colours encoded in `code`, the
+    /// way `code` encodes them, so a code can be shown to the user
+    pub fn from_code(code: u8, guess: &str) -> GuessResult {
+        let mut result = GuessResult([Character::Empty; 5]);
+        for (i, c) in guess.chars().take(5).enumerate() {
+            // the first tile is the most significant digit
+            result.0[i] = match code / 3u8.pow(4 - i as u32) % 3 {
+                2 => Character::Green(c),
+                1 => Character::Yellow(c),
+                _ => Character::Red(c),
+            };
+        }
+        result
+    }
+
+    /// The clue as a row of coloured squares, the way results are usually
+    /// shared, in `palette`'s colours
+    pub fn emoji(&self, palette: Palette) -> String {
+        let (green, yellow) = palette.squares();
+        self.0
+            .iter()
+            .map(|c| match c {
+                Character::Green(_) => green,
+                Character::Yellow(_) => yellow,
+                Character::Red(_) | Character::Empty => '⬛',
+                Character::Wild => '⬜',
+            })
+            .collect()
+    }
+
+    /// Spells the colours out without any terminal colouring: G for green, Y
+    /// for yellow, - for red and * for wild, which [`parse_pattern`] reads back
+    pub fn pattern(&self) -> String {
+        self.0
+            .iter()
+            .map(|c| match c {
+                Character::Green(_) => 'G',
+                Character::Yellow(_) => 'Y',
+                Character::Red(_) | Character::Empty => '-',
+                Character::Wild => '*',
+            })
+            .collect()
+    }
+
+    /// Builds the result of guessing `word` from a pattern, read the way
+    /// [`parse_pattern`] reads it, or `None` if they don't fit together
+    pub fn from_pattern(word: &str, pattern: &str) -> Option<GuessResult> {
+        parse_pattern(word, pattern).ok()
+    }
+
+    /// The guess with each letter coloured as its tile is in `palette`
+    pub fn paint(&self, palette: Palette) -> String {
+        let letters = self
+            .0
+            .iter()
+            .map(|c| c.paint(palette).to_string())
+            .collect::<String>();
+        if colored::control::SHOULD_COLORIZE.should_colorize() {
+            letters
+        } else {
+            // without colours the letters alone don't say anything
+            format!("{} {}", letters, self.pattern())
+        }
+    }
+}
+
+impl std::fmt::Debug for GuessResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.paint(Palette::Standard))
+    }
+}
+
+/// Why a compact result like `gybrr` couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern needs exactly one letter per letter of the guess, and the
+    /// guess has to be 5 letters long
+    WrongLength { guess: usize, pattern: usize },
+    /// Only the letters for each colour, r, - and * mean anything in a pattern
+    InvalidLetter(char, ClueLetters),
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternError::WrongLength { guess, pattern } if guess != pattern => write!(
+                f,
+                "the result has {} letters but the guess has {}",
+                pattern, guess
+            ),
+            PatternError::WrongLength { guess, .. } => {
+                write!(f, "the guess has {} letters, it should have 5", guess)
+            }
+            PatternError::InvalidLetter(c, letters) => write!(
+                f,
+                "'{}' isn't a colour, use {} for green, {} for yellow and {}, r or - for letters not in the word",
+                c, letters.green, letters.yellow, letters.grey
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// The letter typed for each colour when a clue is written as a pattern, like
+/// `gyb` for green, yellow and black
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClueLetters {
+    pub green: char,
+    pub yellow: char,
+    pub grey: char,
+}
+
+impl Default for ClueLetters {
+    fn default() -> Self {
+        ClueLetters {
+            green: 'g',
+            yellow: 'y',
+            grey: 'b',
+        }
+    }
+}
+
+impl ClueLetters {
+    /// The letters for green, yellow and grey, in either case, or `None` if
+    /// they aren't three different letters or one is the wildcard `*`
+    pub fn new(green: char, yellow: char, grey: char) -> Option<ClueLetters> {
+        let (green, yellow, grey) = (
+            green.to_ascii_lowercase(),
+            yellow.to_ascii_lowercase(),
+            grey.to_ascii_lowercase(),
+        );
+        let distinct = green != yellow && yellow != grey && green != grey;
+        (distinct && ![green, yellow, grey].contains(&'*')).then_some(ClueLetters {
+            green,
+            yellow,
+            grey,
+        })
+    }
+}
+
+/// Reads the result of guessing `guess`, one letter per tile: g for green, y
+/// for yellow, b (black), r (red) or - for letters not in the word, and * for a
+/// wild tile, in either case. This is the one way clues are written everywhere,
+/// so what [`GuessResult::pattern`] spells out reads back the same.
+///
+/// ```
+/// use solver::{parse_pattern, ClueLetters, PatternError};
+///
+/// let clue = parse_pattern("crane", "bgybb").unwrap();
+/// assert_eq!(clue.pattern(), "-GY--");
+/// assert_eq!(parse_pattern("crane", "-GY--").unwrap(), clue);
+/// assert_eq!(
+///     parse_pattern("crane", "bgxbb").unwrap_err(),
+///     PatternError::InvalidLetter('x', ClueLetters::default())
+/// );
+/// ```
+pub fn parse_pattern(guess: &str, pattern: &str) -> Result<GuessResult, PatternError> {
+    parse_pattern_with(guess, pattern, ClueLetters::default())
+}
+
+/// Like [`parse_pattern`], but with `letters` standing for green, yellow and
+/// grey instead of g, y and b. r, - and * still mean what they always do,
+/// unless `letters` uses them for something else.
+///
+/// ```
+/// use solver::{parse_pattern, parse_pattern_with, ClueLetters};
+///
+/// let numbers = ClueLetters::new('2', '1', '0').unwrap();
+/// let clue = parse_pattern_with("crane", "01200", numbers).unwrap();
+/// assert_eq!(clue, parse_pattern("crane", "bygbb").unwrap());
+/// ```
+pub fn parse_pattern_with(
+    guess: &str,
+    pattern: &str,
+    letters: ClueLetters,
+) -> Result<GuessResult, PatternError> {
+    let (guess_len, pattern_len) = (guess.chars().count(), pattern.chars().count());
+    if guess_len != 5 || pattern_len != guess_len {
+        return Err(PatternError::WrongLength {
+            guess: guess_len,
+            pattern: pattern_len,
+        });
+    }
+    let mut result = GuessResult([Character::Empty; 5]);
+    for (i, (c, p)) in guess.chars().zip(pattern.chars()).enumerate() {
+        result.0[i] = match p.to_ascii_lowercase() {
+            p if p == letters.green => Character::Green(c),
+            p if p == letters.yellow => Character::Yellow(c),
+            p if p == letters.grey => Character::Red(c),
+            'r' | '-' => Character::Red(c),
+            '*' => Character::Wild,
+            _ => return Err(PatternError::InvalidLetter(p, letters)),
+        };
+    }
+    Ok(result)
+}
+
+/// Why shared results couldn't be read back as clues
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareError {
+    /// There wasn't a single row of squares
+    NoGrid,
+    /// The line with this number was neither a row of squares, a guessed word
+    /// nor the `Wordle` heading
+    UnreadableLine(usize),
+    /// Every row of squares needs the word that was guessed for it
+    MissingWords { rows: usize, words: usize },
+    /// A row and its word didn't make a clue
+    Pattern(PatternError),
+}
+
+impl std::fmt::Display for ShareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareError::NoGrid => write!(f, "there's no grid of squares in it"),
+            ShareError::UnreadableLine(n) => {
+                write!(f, "line {} isn't a row of squares or a guessed word", n)
+            }
+            ShareError::MissingWords { rows, words } => write!(
+                f,
+                "the grid has {} {} but {} {} given",
+                rows,
+                if *rows == 1 { "row" } else { "rows" },
+                words,
+                if *words == 1 {
+                    "word was"
+                } else {
+                    "words were"
+                }
+            ),
+            ShareError::Pattern(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// Reads the grid of squares the NYT shares back into clues, in either palette
+/// and with white or black squares for letters not in the word. The grid
+/// doesn't say what was guessed, so each row needs its word after it, or the
+/// words can be given on lines of their own in the order they were played. A
+/// `Wordle 1,234 4/6` heading is skipped.
+///
+/// ```
+/// use solver::parse_share;
+///
+/// let clues = parse_share("Wordle 1,234 3/6\n\n⬛🟨⬛⬛⬛ crane\n🟦🟧🟦🟦⬛ rowdy\n🟩🟩🟩🟩🟩 world").unwrap();
+/// assert_eq!(clues[1].pattern(), "YGYY-");
+/// assert!(parse_share("crane").is_err());
+/// ```
+pub fn parse_share(text: &str) -> Result<Vec<GuessResult>, ShareError> {
+    let (mut rows, mut words) = (vec![], vec![]);
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("Wordle") {
+            continue;
+        }
+        let squares: String = line
+            .chars()
+            .map_while(|c| match c {
+                '🟩' | '🟧' => Some('g'),
+                '🟨' | '🟦' => Some('y'),
+                '⬛' | '⬜' => Some('b'),
+                _ => None,
+            })
+            .collect();
+        // each square is a single char, so skipping as many chars as there are
+        // squares leaves whatever follows the row
+        let rest: String = line.chars().skip(squares.len()).collect();
+        let rest = rest.trim();
+        if !squares.is_empty() {
+            rows.push(squares);
+        }
+        if !rest.is_empty() {
+            if !rest.chars().all(|c| c.is_alphabetic()) {
+                return Err(ShareError::UnreadableLine(n + 1));
+            }
+            words.push(rest.to_lowercase());
+        }
+    }
+    if rows.is_empty() {
+        return Err(ShareError::NoGrid);
+    }
+    if rows.len() != words.len() {
+        return Err(ShareError::MissingWords {
+            rows: rows.len(),
+            words: words.len(),
+        });
+    }
+    words
+        .iter()
+        .zip(&rows)
+        .map(|(word, row)| parse_pattern(word, row).map_err(ShareError::Pattern))
+        .collect()
+}
+
+/// A word along with how likely the solver thinks it is to be the answer
+#[derive(Debug, Clone)]
+pub struct ScoredWord {
+    pub word: String,
+    pub score: usize,
+}
+
+/// Turns a list of words into one the solver can use, with every word as
+/// likely as the others
+///
+/// ```
+/// let words = solver::scored_words(&["crane", "slate"]);
+/// assert_eq!(words[1].word, "slate");
+/// assert_eq!(words[1].score, 1);
+/// ```
+pub fn scored_words(words: &[&str]) -> Vec<ScoredWord> {
+    words
+        .iter()
+        .map(|word| ScoredWord {
+            word: word.to_string(),
+            score: 1,
+        })
+        .collect()
+}
+
+/// A likely answer, as reported in a [`RoundMessage`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Suggestion {
+    pub word: String,
+    /// The word's share of the candidates' scores
+    pub probability: f64,
+}
+
+/// What the solver sends each round when driven over JSON: how many words are
+/// left, the likeliest of them, and what it would guess next
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RoundMessage {
+    pub candidates: usize,
+    pub suggestions: Vec<Suggestion>,
+    pub guess: String,
+}
+
+impl RoundMessage {
+    /// Reports the ranked `words`, suggesting at most `top` of them
+    pub fn new(words: &[ScoredWord], guess: &str, top: usize) -> Self {
+        let total_score = words.iter().map(|sw| sw.score).sum::<usize>().max(1);
+        RoundMessage {
+            candidates: words.len(),
+            suggestions: words
+                .iter()
+                .take(top)
+                .map(|sw| Suggestion {
+                    word: sw.word.clone(),
+                    probability: sw.score as f64 / total_score as f64,
+                })
+                .collect(),
+            guess: guess.to_string(),
+        }
+    }
+}
+
+/// A clue sent to the solver over JSON: the word that was played, and its
+/// result in the form [`parse_pattern`] reads
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClueMessage {
+    pub guess: String,
+    pub pattern: String,
+}
+
+/// Sent instead of a [`RoundMessage`] when a clue couldn't be used, after
+/// which the solver waits for another
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ErrorMessage {
+    pub error: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Character {
+    /// The character is in the word, but not in the correct position
+    Yellow(char),
+    /// The character is in the word, and in the correct position
+    Green(char),
+    /// The character is not in the word
+    Red(char),
+    /// A special tile used by some puzzle variants, meaning this position could be
+    /// any letter. It tells us nothing, so it never constrains the word
+    Wild,
+    /// Used only as a placeholder during user input
+    Empty,
+}
+
+impl Character {
+    /// The letter, coloured as its tile is in `palette`
+    pub fn paint(&self, palette: Palette) -> ColoredString {
+        match self {
+            Character::Yellow(c) => palette.yellow(&c.to_string()),
+            Character::Green(c) => palette.green(&c.to_string()),
+            Character::Red(c) => palette.red(&c.to_string()),
+            Character::Wild => "*".magenta(),
+            Character::Empty => "-".blue(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Character {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.paint(Palette::Standard))
+    }
+}
+
+/// Filters a wordlist based on previous guess results
+///
+/// ```
+/// use solver::{
+///     calculate_guess_result, filter_using_known_info, optimise_results, scored_words, GuessResult,
+/// };
+///
+/// let words = scored_words(&["crane", "crate", "slate"]);
+/// // the answer is "crate", and we guessed "crane"
+/// let clue = calculate_guess_result(&"crate".to_string(), &"crane".to_string());
+///
+/// let remaining = filter_using_known_info(&words, &vec![clue]);
+/// assert_eq!(remaining.len(), 1);
+/// assert_eq!(remaining[0].word, "crate");
+///
+/// // mistyped clues can contradict each other, which leaves nothing rather
+/// // than panicking
+/// let clues = vec![
+///     GuessResult::from_pattern("crane", "GGG-G").unwrap(),
+///     GuessResult::from_pattern("slate", "GGGGG").unwrap(),
+/// ];
+/// let remaining = filter_using_known_info(&words, &clues);
+/// assert!(remaining.is_empty());
+/// assert!(optimise_results(remaining, &clues).is_empty());
+/// ```
+pub fn filter_using_known_info(
+    words: &[ScoredWord],
+    known_info: &[GuessResult],
+) -> Vec<ScoredWord> {
+    // we have a list of words, and we know some information about the word we're
+    // looking for we process the words finding possible words that match
+    // **all** the known information
+    let bounds: Vec<HashMap<char, (usize, Option<usize>)>> =
+        known_info.iter().map(letter_bounds).collect();
+    // the yellows from every clue together, which rule most words out before
+    // each clue has to be checked on its own
+    let forbidden = forbidden_positions(known_info);
+    let forced = forced_positions(known_info);
+    words
+        .iter()
+        .filter(|sw| {
+            let chars: Vec<char> = sw.word.chars().collect();
+            chars
+                .iter()
+                .enumerate()
+                .all(|(i, c)| !matches!(forbidden.get(c), Some(positions) if positions[i]))
+        })
+        .filter(|sw| {
+            forced
+                .iter()
+                .all(|(t, i)| sw.word.chars().nth(*i) == Some(*t))
+        })
+        .filter(|sw| {
+            known_info.iter().zip(&bounds).all(|(guess, bounds)| {
+                guess
+                    .0
+                    .iter()
+                    .enumerate()
+                    .all(|(i, c)| matches_tile(&sw.word, guess, i, c))
+                    && bounds
+                        .iter()
+                        .all(|(t, bound)| matches_count(&sw.word, *t, *bound))
+            })
+        })
+        .map(|sw| sw.clone())
+        .collect()
+}
+
+/// Gathers the positions each yellow letter can't be in across all the clues,
+/// since a yellow only says the letter isn't where it was guessed
+pub fn forbidden_positions(known_info: &[GuessResult]) -> HashMap<char, [bool; 5]> {
+    let mut forbidden: HashMap<char, [bool; 5]> = HashMap::new();
+    for guess in known_info {
+        for (i, c) in guess.0.iter().enumerate() {
+            if let Character::Yellow(t) = c {
+                forbidden.entry(*t).or_insert([false; 5])[i] = true;
+            }
+        }
+    }
+    forbidden
+}
+
+/// The yellow letters that have only one position left, once the positions
+/// they've been yellow in and those other letters are green in are ruled out.
+/// A letter that's been green, or yellow more than once in a clue, could have
+/// copies anywhere, so it's left out
+///
+/// ```
+/// use solver::{forced_positions, GuessResult};
+///
+/// // s and m are green at the end, and a has been yellow first and second
+/// let clues = vec![
+///     GuessResult::from_pattern("abbbb", "Y----").unwrap(),
+///     GuessResult::from_pattern("daddd", "-Y---").unwrap(),
+///     GuessResult::from_pattern("eeesm", "---GG").unwrap(),
+/// ];
+/// assert_eq!(forced_positions(&clues).get(&'a'), Some(&2));
+/// ```
+pub fn forced_positions(known_info: &[GuessResult]) -> HashMap<char, usize> {
+    let mut taken = [false; 5];
+    let mut repeated: HashSet<char> = HashSet::new();
+    for guess in known_info {
+        let mut seen: HashSet<char> = HashSet::new();
+        for (i, c) in guess.0.iter().enumerate() {
+            match c {
+                Character::Green(t) => {
+                    taken[i] = true;
+                    repeated.insert(*t);
+                }
+                Character::Yellow(t) if !seen.insert(*t) => {
+                    repeated.insert(*t);
+                }
+                _ => {}
+            }
+        }
+    }
+    forbidden_positions(known_info)
+        .into_iter()
+        .filter(|(t, _)| !repeated.contains(t))
+        .filter_map(|(t, forbidden)| {
+            let mut open = (0..5).filter(|i| !forbidden[*i] && !taken[*i]);
+            match (open.next(), open.next()) {
+                (Some(i), None) => Some((t, i)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Works out how many copies of each letter a guess result says the answer
+/// has. Every green or yellow copy is one the answer must have, and a red copy
+/// alongside them means it has no more than that. Letters that are only red
+/// are left to `matches_tile`.
+pub fn letter_bounds(guess: &GuessResult) -> HashMap<char, (usize, Option<usize>)> {
+    let mut bounds: HashMap<char, (usize, Option<usize>)> = HashMap::new();
+    for c in &guess.0 {
+        if let Character::Green(t) | Character::Yellow(t) = c {
+            bounds.entry(*t).or_insert((0, None)).0 += 1;
+        }
+    }
+    for c in &guess.0 {
+        if let Character::Red(t) = c {
+            if let Some((min, max)) = bounds.get_mut(t) {
+                *max = Some(*min);
+            }
+        }
+    }
+    bounds
+}
+
+/// Checks a word has between `min` and `max` copies of a letter
+pub fn matches_count(word: &str, t: char, (min, max): (usize, Option<usize>)) -> bool {
+    let count = word.chars().filter(|c| *c == t).count();
+    count >= min && !matches!(max, Some(max) if count > max)
+}
+
+/// Checks a word against a single tile of a guess result at position `i`. The
+/// rest of the guess is needed to tell what a red tile rules out.
+pub fn matches_tile(word: &str, guess: &GuessResult, i: usize, c: &Character) -> bool {
+    match c {
+        // word contains all yellow characters
+        Character::Yellow(t) => word.contains(*t) && word.chars().nth(i).unwrap() != *t,
+        // word contains all green characters in the correct position
+        Character::Green(t) => word.chars().nth(i).unwrap() == *t,
+        // a red copy of a letter that's green or yellow elsewhere only means the
+        // answer has no more copies, so all we know is it isn't here
+        Character::Red(t)
+            if guess.0.iter().any(
+                |other| matches!(other, Character::Green(o) | Character::Yellow(o) if o == t),
+            ) =>
+        {
+            word.chars().nth(i).unwrap() != *t
+        }
+        // otherwise, word doesn't contain any red characters
+        Character::Red(t) => !word.contains(*t),
+        // wildcards could be any letter, so don't rule anything out
+        Character::Wild => true,
+        Character::Empty => unreachable!("Empty character in known_info"),
+    }
+}
+
+/// The positions `optimise_results` scores, which are those where every clue so
+/// far was yellow, leaving out any a yellow letter is forced into, as every
+/// word left has it there just as if it were green
+pub fn scored_positions(known_info: &[GuessResult]) -> Vec<usize> {
+    let forced: Vec<usize> = forced_positions(known_info).into_values().collect();
+    (0..5)
+        .filter(|i| !forced.contains(i))
+        .filter(|i| {
+            known_info
+                .iter()
+                .all(|guess| matches!(guess.0[*i], Character::Yellow(_)))
+        })
+        .collect()
+}
+
+/// reorders a wordlist to optimise the next guess using the strategy. A letter
+/// only adds to a word's score once, however many scored positions it fills,
+/// since testing it twice tells us less than testing two different letters.
+///
+/// ```
+/// use solver::{
+///     optimise_results, optimise_results_counting_repeats, scored_words, GuessResult, ScoredWord,
+/// };
+///
+/// let words = scored_words(&["sassy", "slate", "sales", "sense"]);
+/// // every position has only had a yellow clue, so every position is scored
+/// let clues = vec![GuessResult::from_pattern("xxxxx", "YYYYY").unwrap()];
+///
+/// let rank = |ranked: Vec<ScoredWord>, word: &str| ranked.iter().position(|sw| sw.word == word);
+/// let ranked = optimise_results(words.clone(), &clues);
+/// assert!(rank(ranked.clone(), "slate") < rank(ranked, "sassy"));
+/// // scoring each of its s's would put "sassy" first
+/// let ranked = optimise_results_counting_repeats(words, &clues);
+/// assert!(rank(ranked.clone(), "sassy") < rank(ranked, "slate"));
+/// ```
+pub fn optimise_results(results: Vec<ScoredWord>, known_info: &[GuessResult]) -> Vec<ScoredWord> {
+    rank_by_yellows(results, known_info, false)
+}
+
+/// Like `optimise_results`, but a repeated letter adds to the score in every
+/// scored position it fills
+pub fn optimise_results_counting_repeats(
+    results: Vec<ScoredWord>,
+    known_info: &[GuessResult],
+) -> Vec<ScoredWord> {
+    rank_by_yellows(results, known_info, true)
+}
+
+fn rank_by_yellows(
+    results: Vec<ScoredWord>,
+    known_info: &[GuessResult],
+    count_repeats: bool,
+) -> Vec<ScoredWord> {
+    // if the length is 0, no optimisation is required
+    if results.len() == 0 {
+        return results;
+    }
+
+    // at this stage, the filter has ensured that any red characters are not in the
+    // word, and all green characters are already in their correct positions.
+    // we therefore score based upon the yellow characters exclusively,
+    // so the list of results is sorted to lower the maximum guesses to find the
+    // word
+
+    // this is done by scoring each word based on the frequency of the yellow
+    // characters
+
+    // example: we make these two guesses:
+    // [Red(D), Green(R), Red(U), Yellow(N), Red(K)]
+    // [Red(F), Red(I), Yellow(G), Red(H), Red(T)]
+    // which narrows the wordlist down to:
+    // groan, green, grown
+
+    // we can identify that the first character has to be 'g', the second is 'r' and
+    // the last is 'n' the differences between the words are therefore the third
+    // and fourth characters:   'o' and 'e'
+    // for position 3, 'o' is the most common character, so words with 'o' in
+    // position 3 are more likely to be the word than words with 'e' in position
+    // 3 "grown" and "groan" are equally likely to be the word, as their uniqueness
+    // is the same, but "green" is less likely
+
+    // ALGORITHM:
+    // 1. count the frequency of each character in each position using a
+    //    [[usize; 26]; 5], ensuring to ignore any green or red characters
+    // 2. score each word based on the frequency of the yellow characters
+    // 3. sort the words by their score
+
+    // if there are no positions to score, every word would score 1, so skip
+    // counting and just sort them
+    let scored_positions = scored_positions(known_info);
+    if scored_positions.is_empty() {
+        let mut words: Vec<ScoredWord> = results
+            .into_iter()
+            .map(|sw| ScoredWord {
+                word: sw.word,
+                score: 1,
+            })
+            .collect();
+        words.sort_by(|a, b| a.word.cmp(&b.word));
+        return words;
+    }
+
+    let frequencies: [[usize; 26]; 5] = results.iter().fold(
+        [[0; 26], [0; 26], [0; 26], [0; 26], [0; 26]],
+        |mut acc, sw| {
+            for (i, c) in sw.word.chars().enumerate() {
+                if let Some(letter) = letter_index(c) {
+                    acc[i][letter] += 1;
+                }
+            }
+            acc
+        },
+    );
+
+    let mut scored_words = results
+        .par_iter()
+        .map(|sw| {
+            let mut score = 1;
+            let chars: Vec<char> = sw.word.chars().collect();
+            let mut seen = [false; 26];
+            for &i in &scored_positions {
+                if let Some(letter) = letter_index(chars[i]) {
+                    if count_repeats || !std::mem::replace(&mut seen[letter], true) {
+                        score += frequencies[i][letter];
+                    }
+                }
+            }
+            ScoredWord {
+                word: sw.word.clone(),
+                score,
+            }
+        })
+        .collect::<Vec<ScoredWord>>();
+    // ties are broken alphabetically, so the suggestions and the list of options
+    // shown at the end come out the same every run, whatever order the candidates
+    // arrived in
+    scored_words.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.word.cmp(&b.word)));
+    scored_words
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes, PartialEq, Eq, Hash))]
+pub enum Strategy {
+    FrequencySimple,
+    FrequencyPositionAware,
+    Random,
+    /// Found by `--approx-opener`, so never tested against the others
+    Annealed,
+    /// The word whose clues tell us the most on average
+    Entropy,
+    /// The word that leaves the fewest words in the worst case
+    Minimax,
+    /// The real word whose distinct letters are most common in their positions
+    FrequencyPositionalWord,
+}
+
+/// The index of a letter in the 26-long frequency arrays, or `None` for anything
+/// that isn't a lowercase ascii letter, which can't be counted
+pub fn letter_index(c: char) -> Option<usize> {
+    c.is_ascii_lowercase().then(|| c as usize - 'a' as usize)
+}
+
+/// Counts how often each letter appears in each position. Each word counts as
+/// many times as its score, which is 1 unless answer frequencies were loaded.
+pub fn positional_frequencies(words: &[ScoredWord]) -> [[usize; 26]; 5] {
+    words.iter().fold([[0; 26]; 5], |mut acc, sw| {
+        for (i, c) in sw.word.chars().enumerate() {
+            if let Some(letter) = letter_index(c) {
+                acc[i][letter] += sw.score;
+            }
+        }
+        acc
+    })
+}
+
+/// How often each pair of letters appears side by side across the words,
+/// indexed by the first letter and then the second
+pub fn bigram_frequencies(words: &[ScoredWord]) -> [[usize; 26]; 26] {
+    words.iter().fold([[0; 26]; 26], |mut acc, sw| {
+        let letters: Vec<Option<usize>> = sw.word.chars().map(letter_index).collect();
+        for pair in letters.windows(2) {
+            if let [Some(first), Some(second)] = pair {
+                acc[*first][*second] += 1;
+            }
+        }
+        acc
+    })
+}
+
+/// Returns the optimal starting guess for the wordset. Only the `Random`
+/// strategy draws from `rng`.
+///
+/// ```
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use solver::{get_first_guess, scored_words, Strategy};
+///
+/// let words = scored_words(&["sassy", "slate", "crane", "pious", "fluff"]);
+/// let opener = get_first_guess(&words, Strategy::FrequencyPositionalWord, &mut StdRng::seed_from_u64(0));
+/// // unlike the position-aware opener, it's always a real word
+/// assert!(words.iter().any(|sw| sw.word == opener));
+///
+/// // a random opener is only as random as the rng it's given
+/// let random = |seed| get_first_guess(&words, Strategy::Random, &mut StdRng::seed_from_u64(seed));
+/// assert_eq!(random(1), random(1));
+/// ```
+pub fn get_first_guess(words: &Vec<ScoredWord>, strategy: Strategy, rng: &mut StdRng) -> String {
+    get_first_guess_from(words, words, strategy, rng)
+}
+
+/// Like [`get_first_guess`], but strategies that open with a real word pick it
+/// from `guesses`, which can include words that are never the answer. They're
+/// still scored by how they do against `words`.
+///
+/// ```
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use solver::{get_first_guess_from, scored_words, Strategy};
+///
+/// let answers = scored_words(&["hatch", "catch", "match", "latch", "patch"]);
+/// let guesses = scored_words(&["hatch", "catch", "match", "latch", "patch", "clamp"]);
+/// // clamp can't be the answer, but tells every one of them apart
+/// let opener = get_first_guess_from(&answers, &guesses, Strategy::Entropy, &mut StdRng::seed_from_u64(0));
+/// assert_eq!(opener, "clamp");
+/// ```
+pub fn get_first_guess_from(
+    words: &Vec<ScoredWord>,
+    guesses: &[ScoredWord],
+    strategy: Strategy,
+    rng: &mut StdRng,
+) -> String {
+    get_first_guess_using(
+        words,
+        guesses,
+        strategy,
+        &positional_frequencies(words),
+        rng,
+    )
+}
+
+/// Like [`get_first_guess_from`], with the [`positional_frequencies`] of
+/// `words` already counted, so they can be counted once for a word list and
+/// reused by every strategy
+pub fn get_first_guess_using(
+    words: &Vec<ScoredWord>,
+    guesses: &[ScoredWord],
+    strategy: Strategy,
+    frequencies: &[[usize; 26]; 5],
+    rng: &mut StdRng,
+) -> String {
+    match strategy {
+        Strategy::FrequencyPositionAware => {
+            // our first guess is constructed off the most common character in each
+            // position
+            // find the most likely character in each position
+            let mut guess = String::new();
+            for freq in frequencies.iter() {
+                let mut max = 0;
+                let mut max_index = 0;
+                for (i, count) in freq.iter().enumerate() {
+                    if *count > max {
+                        max = *count;
+                        max_index = i;
+                    }
+                }
+                guess.push((max_index + 97) as u8 as char);
+            }
+
+            return guess;
+        }
+        Strategy::FrequencySimple => {
+            // count how many words each character is in and take the top 5. a
+            // word only counts once for a letter however often it repeats it, so
+            // words like "sassy" don't inflate letters they test no better
+            let mut char_counts: [usize; 26] = [0; 26];
+            for sw in words {
+                let mut seen = [false; 26];
+                for c in sw.word.chars() {
+                    if let Some(letter) = letter_index(c) {
+                        if !std::mem::replace(&mut seen[letter], true) {
+                            char_counts[letter] += 1;
+                        }
+                    }
+                }
+            }
+
+            // sort by count
+            let mut char_counts: Vec<(usize, char)> = char_counts
+                .iter()
+                .enumerate()
+                .map(|(i, count)| (*count, (i + 97) as u8 as char))
+                .collect();
+            char_counts.sort_by(|a, b| b.0.cmp(&a.0));
+
+            // take the top 5
+            let mut guess = String::new();
+            for (_, c) in char_counts.iter().take(5) {
+                guess.push(*c);
+            }
+
+            return guess;
+        }
+        Strategy::Annealed => anneal_opener(words, guesses, None, rng),
+        Strategy::Entropy => {
+            // every word is tried against every other, so this is the slowest
+            // strategy by far, but it only has to run once per word list
+            guesses
+                .par_iter()
+                .map(|sw| (partition_entropy(&partition(&sw.word, words)), &sw.word))
+                .max_by(|a, b| a.0.total_cmp(&b.0).then(b.1.cmp(a.1)))
+                .map(|(_, word)| word.clone())
+                .expect("word list is empty")
+        }
+        Strategy::FrequencyPositionalWord => {
+            // a repeated letter only counts where it first appears, so doubles
+            // aren't rewarded for telling us about the same letter twice
+            let score = |word: &str| {
+                let mut seen = [false; 26];
+                let mut score = 0;
+                for (i, c) in word.chars().enumerate().take(5) {
+                    if let Some(letter) = letter_index(c) {
+                        if !std::mem::replace(&mut seen[letter], true) {
+                            score += frequencies[i][letter];
+                        }
+                    }
+                }
+                score
+            };
+            guesses
+                .par_iter()
+                .map(|sw| (score(&sw.word), &sw.word))
+                .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(a.1)))
+                .map(|(_, word)| word.clone())
+                .expect("word list is empty")
+        }
+        Strategy::Minimax => guesses
+            .par_iter()
+            .map(|sw| (worst_case(&partition(&sw.word, words)), &sw.word))
+            .min()
+            .map(|(_, word)| word.clone())
+            .expect("word list is empty"),
+        Strategy::Random => {
+            // create 5 random characters
+            let mut guess = String::new();
+            for _ in 0..5 {
+                guess.push(rng.gen_range('a'..='z'));
+            }
+            return guess;
+        }
+    }
+}
+
+/// Searches `allowed` for an opener that leaves few of `words` on average by
+/// simulated annealing: starting from a random word, it keeps moving to a word
+/// one letter different, or now and then to any word at all, always taking
+/// moves that help and sometimes ones that don't, less often as it goes on.
+/// Returns the best opener seen when it runs out of steps or the deadline
+/// passes, which is always on the list.
+pub fn anneal_opener(
+    words: &[ScoredWord],
+    allowed: &[ScoredWord],
+    deadline: Option<Instant>,
+    rng: &mut StdRng,
+) -> String {
+    let cost = |i: usize| expected_remaining(&partition(&allowed[i].word, words));
+
+    // words one letter apart share a word with that letter blanked out, so
+    // grouping by those finds every word's neighbours without comparing pairs
+    let mut blanked: HashMap<(usize, String), Vec<usize>> = HashMap::new();
+    for (i, sw) in allowed.iter().enumerate() {
+        for position in 0..sw.word.len() {
+            let mut key = sw.word.clone().into_bytes();
+            key[position] = b'_';
+            blanked
+                .entry((position, String::from_utf8_lossy(&key).into_owned()))
+                .or_default()
+                .push(i);
+        }
+    }
+    let neighbour = |i: usize, rng: &mut StdRng| {
+        let word = &allowed[i].word;
+        let position = rng.gen_range(0..word.len());
+        let mut key = word.clone().into_bytes();
+        key[position] = b'_';
+        let group = &blanked[&(position, String::from_utf8_lossy(&key).into_owned())];
+        if group.len() > 1 && rng.gen::<f64>() >= ANNEAL_RESTART {
+            // the group includes the word itself, so pick from the others
+            let j = rng.gen_range(0..group.len() - 1);
+            if group[j] == i {
+                group[group.len() - 1]
+            } else {
+                group[j]
+            }
+        } else {
+            rng.gen_range(0..allowed.len())
+        }
+    };
+
+    let mut current = rng.gen_range(0..allowed.len());
+    let mut current_cost = cost(current);
+    let mut best = (current, current_cost);
+
+    for step in 0..ANNEAL_STEPS {
+        if matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+            break;
+        }
+        let candidate = neighbour(current, rng);
+        let candidate_cost = cost(candidate);
+
+        // how willing we are to take a worse opener, falling from 1 to 0
+        let temperature = 1.0 - step as f64 / ANNEAL_STEPS as f64;
+        let worse_by = (candidate_cost - current_cost) / current_cost.max(1.0);
+        if worse_by <= 0.0 || rng.gen::<f64>() < (-worse_by / (0.1 * temperature)).exp() {
+            current = candidate;
+            current_cost = candidate_cost;
+            if current_cost < best.1 {
+                best = (current, current_cost);
+            }
+        }
+    }
+
+    allowed[best.0].word.clone()
+}
+
+/// Splits the candidates by the clue a guess would get against each of them,
+/// returning the size of each group keyed by its pattern code
+pub fn partition(guess: &String, words: &[ScoredWord]) -> HashMap<u8, usize> {
+    // codes are small enough to count straight into an array
+    let mut counts = [0; 243];
+    for sw in words {
+        counts[pattern_code(guess.as_bytes(), sw.word.as_bytes()) as usize] += 1;
+    }
+    (0..=242)
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// The number of candidates expected to be left after a guess, given how it
+/// partitions them. Each group is as likely as its share of the candidates.
+pub fn expected_remaining(buckets: &HashMap<u8, usize>) -> f64 {
+    let total: usize = buckets.values().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    buckets
+        .values()
+        .map(|size| (size * size) as f64)
+        .sum::<f64>()
+        / total as f64
+}
+
+/// The most candidates a guess could leave, whatever its clue
+pub fn worst_case(buckets: &HashMap<u8, usize>) -> usize {
+    buckets.values().max().copied().unwrap_or(0)
+}
+
+/// The Shannon entropy, in bits, of how a guess partitions the candidates:
+/// how much we expect to learn from its clue
+pub fn partition_entropy(buckets: &HashMap<u8, usize>) -> f64 {
+    let total: usize = buckets.values().sum();
+    buckets
+        .values()
+        .map(|size| {
+            let p = *size as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Orders candidates so the guesses expected to leave the fewest words come
+/// first. The sort is stable, so words that split equally well stay in the
+/// order they came in.
+pub fn rank_by_expected_remaining(words: Vec<ScoredWord>) -> Vec<ScoredWord> {
+    let mut ranked: Vec<(f64, ScoredWord)> = words
+        .par_iter()
+        .map(|sw| (expected_remaining(&partition(&sw.word, &words)), sw.clone()))
+        .collect();
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ranked.into_iter().map(|(_, sw)| sw).collect()
+}
+
+/// Orders candidates so the guesses that leave the fewest words in the worst
+/// case come first, keeping ties in the order they came in
+pub fn rank_by_worst_case(words: Vec<ScoredWord>) -> Vec<ScoredWord> {
+    let mut ranked: Vec<(usize, ScoredWord)> = words
+        .par_iter()
+        .map(|sw| (worst_case(&partition(&sw.word, &words)), sw.clone()))
+        .collect();
+    ranked.sort_by_key(|(worst, _)| *worst);
+    ranked.into_iter().map(|(_, sw)| sw).collect()
+}
+
+/// Moves words with repeated letters down the ranking, by how much depending on
+/// how many words remain. Scores are left alone, so the likelihoods shown don't
+/// change, and the sort is stable so ties keep their order.
+pub fn penalise_repeats(words: &mut [ScoredWord]) {
+    // with this few words left there's no penalty, so nothing to reorder
+    if words.len() <= REPEAT_PENALTY_NONE {
+        return;
+    }
+    let ranking_score = repeat_penalised_score(words.len());
+    words.sort_by(|a, b| ranking_score(b).total_cmp(&ranking_score(a)));
+}
+
+/// What `penalise_repeats` orders words by when `remaining` words are left
+fn repeat_penalised_score(remaining: usize) -> impl Fn(&ScoredWord) -> f64 {
+    let strength = (remaining.saturating_sub(REPEAT_PENALTY_NONE) as f64
+        / (REPEAT_PENALTY_FULL - REPEAT_PENALTY_NONE) as f64)
+        .min(1.0);
+    move |sw: &ScoredWord| {
+        let letters: HashSet<char> = sw.word.chars().collect();
+        if letters.len() < sw.word.len() {
+            sw.score as f64 * (1.0 - REPEAT_PENALTY * strength)
+        } else {
+            sw.score as f64
+        }
+    }
+}
+
+/// Reorders candidates that scored about the same so those made of commoner
+/// letter pairs come first. Only pairs with a letter that isn't green yet
+/// count, since every candidate shares the rest. Scores are left as they are,
+/// as are the words themselves.
+pub fn favour_common_bigrams(
+    words: &mut [ScoredWord],
+    known_info: &[GuessResult],
+    bigrams: &[[usize; 26]; 26],
+) {
+    if bigrams.iter().flatten().all(|count| *count == 0) {
+        return;
+    }
+    let ranking_score = bigram_score(known_info, bigrams);
+    words.sort_by(|a, b| ranking_score(b).total_cmp(&ranking_score(a)));
+}
+
+/// What `favour_common_bigrams` orders words by
+fn bigram_score<'a>(
+    known_info: &'a [GuessResult],
+    bigrams: &'a [[usize; 26]; 26],
+) -> impl Fn(&ScoredWord) -> f64 + 'a {
+    let most = bigrams.iter().flatten().max().copied().unwrap_or(0);
+    let green = move |i: usize| {
+        known_info
+            .iter()
+            .any(|guess| matches!(guess.0.get(i), Some(Character::Green(_))))
+    };
+    move |sw: &ScoredWord| {
+        let letters: Vec<Option<usize>> = sw.word.chars().map(letter_index).collect();
+        let counts: Vec<usize> = letters
+            .windows(2)
+            .enumerate()
+            .filter(|(i, _)| !(green(*i) && green(i + 1)))
+            .filter_map(|(_, pair)| match pair {
+                [Some(first), Some(second)] => Some(bigrams[*first][*second]),
+                _ => None,
+            })
+            .collect();
+        if counts.is_empty() || most == 0 {
+            return sw.score as f64;
+        }
+        let commonness = counts.iter().sum::<usize>() as f64 / (most * counts.len()) as f64;
+        sw.score as f64 * (1.0 + BIGRAM_BONUS * commonness)
+    }
+}
+
+/// Scores each word by its weight from the word list, so the scores are
+/// proportional to how likely each is to be the answer. The sort is stable, so
+/// equally weighted words keep their order by letter frequency.
+pub fn apply_weights(words: &mut [ScoredWord], weights: &HashMap<String, usize>) {
+    for sw in words.iter_mut() {
+        sw.score = weights.get(&sw.word).copied().unwrap_or(1);
+    }
+    words.sort_by_key(|sw| std::cmp::Reverse(sw.score));
+}
+
+/// Groups the words into clusters of anagrams (same letters, different
+/// positions), largest first. Words without an anagram in the set are left out.
+pub fn find_anagram_clusters(words: &[ScoredWord]) -> Vec<Vec<String>> {
+    let mut clusters: HashMap<Vec<char>, Vec<String>> = HashMap::new();
+    for sw in words {
+        let mut letters: Vec<char> = sw.word.chars().collect();
+        letters.sort_unstable();
+        clusters.entry(letters).or_default().push(sw.word.clone());
+    }
+
+    let mut clusters: Vec<Vec<String>> = clusters
+        .into_values()
+        .filter(|cluster| cluster.len() > 1)
+        .collect();
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    clusters
+}
+
+/// Counts how many distinct colour patterns a guess produces across a set of
+/// possible answers. A guess that gives every answer its own pattern is
+/// guaranteed to tell them apart.
+pub fn count_distinct_patterns(guess: &String, answers: &[String]) -> usize {
+    let mut patterns: Vec<u8> = answers
+        .iter()
+        .map(|answer| pattern_code(guess.as_bytes(), answer.as_bytes()))
+        .collect();
+    patterns.sort_unstable();
+    patterns.dedup();
+    patterns.len()
+}
+
+/// If an anagram cluster makes up more than half of the remaining candidates,
+/// returns the cluster along with the word from the full list that best
+/// separates its members by position. Returns `None` when there is no such
+/// cluster, or when the top candidate already separates it just as well.
+pub fn anagram_probe(
+    words: &[ScoredWord],
+    all_words: &[ScoredWord],
+) -> Option<(Vec<String>, String)> {
+    let cluster = find_anagram_clusters(words).into_iter().next()?;
+    if cluster.len() * 2 <= words.len() {
+        return None;
+    }
+
+    // prefer words that split the cluster the most, and of those, prefer words in
+    // the cluster itself since they could also be the answer
+    let (probe, separated) = all_words
+        .par_iter()
+        .map(|sw| {
+            let separated = count_distinct_patterns(&sw.word, &cluster);
+            (sw, (separated, cluster.contains(&sw.word)))
+        })
+        .max_by(|(a, a_key), (b, b_key)| a_key.cmp(b_key).then_with(|| b.word.cmp(&a.word)))
+        .map(|(sw, (separated, _))| (sw.word.clone(), separated))?;
+
+    if separated <= count_distinct_patterns(&words.first()?.word, &cluster) {
+        return None;
+    }
+
+    Some((cluster, probe))
+}
+
+/// Why [`choose_next_guess`] picked the guess it did
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NextGuess {
+    /// The next of the fixed openers, played whatever the clues so far
+    Opener(String),
+    /// A word that tells apart the anagrams making up most of the candidates
+    Probe { cluster: Vec<String>, probe: String },
+    /// The top ranked candidate
+    Likeliest(String),
+}
+
+impl NextGuess {
+    /// The word to guess
+    pub fn word(&self) -> &str {
+        match self {
+            NextGuess::Opener(word) | NextGuess::Likeliest(word) => word,
+            NextGuess::Probe { probe, .. } => probe,
+        }
+    }
+}
+
+/// What to guess after `round` clues, given the ranked candidates: the next
+/// fixed opener if there is one and the answer isn't known yet, then a probe if
+/// anagrams make up most of what's left, or else the likeliest word. In `hard`
+/// mode a fixed opener that no longer fits the clues is skipped, since it
+/// couldn't be played. `None` if there are no candidates to pick from.
+pub fn choose_next_guess(
+    words: &[ScoredWord],
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    round: usize,
+    hard: bool,
+) -> Option<NextGuess> {
+    // hard mode only allows guesses that could be the answer
+    let probe_pool = if hard { words } else { all_words };
+    let playable = |opener: &&&str| !hard || words.iter().any(|sw| sw.word == **opener);
+    if let Some(opener) = openers
+        .get(round)
+        .filter(|_| words.len() > 1)
+        .filter(playable)
+    {
+        return Some(NextGuess::Opener(opener.to_string()));
+    }
+    match anagram_probe(words, probe_pool) {
+        Some((cluster, probe)) => Some(NextGuess::Probe { cluster, probe }),
+        None => words
+            .first()
+            .map(|sw| NextGuess::Likeliest(sw.word.clone())),
+    }
+}
+
+/// Just the word [`choose_next_guess`] picks, or an empty string if there are
+/// no candidates
+pub fn next_guess(
+    words: &[ScoredWord],
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    round: usize,
+    hard: bool,
+) -> String {
+    choose_next_guess(words, all_words, openers, round, hard)
+        .map(|guess| guess.word().to_string())
+        .unwrap_or_default()
+}
+
+/// How candidates are ranked after each clue. Unlike the strategy, which only
+/// picks the opener, this can be switched mid-session with `strategy <name>`, and is
+/// chosen with `--scorer`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Scorer {
+    /// Most likely words first, judged by letter frequency
+    #[default]
+    Frequency,
+    /// Words that leave the fewest candidates on average first, which pays off
+    /// once only a handful of words are left
+    Entropy,
+    /// Words that leave the fewest candidates in the worst case first
+    Minimax,
+}
+
+impl Scorer {
+    /// Reads a scorer's name: frequency, entropy or minimax
+    pub fn parse(name: &str) -> Option<Scorer> {
+        match name {
+            "frequency" => Some(Scorer::Frequency),
+            "entropy" => Some(Scorer::Entropy),
+            "minimax" => Some(Scorer::Minimax),
+            _ => None,
+        }
+    }
+
+    /// Scores and orders the candidates. Scores always come from frequency, or
+    /// from the word list's weights when it has them, so the likelihoods shown
+    /// stay meaningful whichever scorer picks the order.
+    pub fn rank(
+        self,
+        results: Vec<ScoredWord>,
+        known_info: &[GuessResult],
+        count_repeats: bool,
+        weights: Option<&HashMap<String, usize>>,
+    ) -> Vec<ScoredWord> {
+        let mut words = if count_repeats {
+            optimise_results_counting_repeats(results, known_info)
+        } else {
+            optimise_results(results, known_info)
+        };
+        if let Some(weights) = weights {
+            apply_weights(&mut words, weights);
+        }
+        match self {
+            Scorer::Frequency => words,
+            Scorer::Entropy => rank_by_expected_remaining(words),
+            Scorer::Minimax => rank_by_worst_case(words),
+        }
+    }
+
+    /// What this scorer orders `sw` by among `words`, lowest first
+    fn order_key(self, sw: &ScoredWord, words: &[ScoredWord]) -> f64 {
+        match self {
+            Scorer::Frequency => -(sw.score as f64),
+            Scorer::Entropy => expected_remaining(&partition(&sw.word, words)),
+            Scorer::Minimax => worst_case(&partition(&sw.word, words)) as f64,
+        }
+    }
+}
+
+/// Everything that decides the order candidates are suggested in: the scorer,
+/// and the tweaks a session can turn on over it. The default ranks by
+/// frequency alone.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ranking<'a> {
+    pub scorer: Scorer,
+    /// Score a repeated letter in every position it fills, like `--count-repeats`
+    pub count_repeats: bool,
+    /// Move words with repeated letters down, like `--penalise-repeats`
+    pub penalise_repeats: bool,
+    /// The word list's weights, when it has them
+    pub weights: Option<&'a HashMap<String, usize>>,
+    /// How common each pair of letters is, with `--bigrams`
+    pub bigrams: Option<[[usize; 26]; 26]>,
+    /// Shuffle words the ranking can't tell apart, reproducibly from this seed,
+    /// like `--tie-seed`
+    pub tie_seed: Option<u64>,
+}
+
+impl Ranking<'_> {
+    /// Scores and orders the candidates left after `known_info`, the same way
+    /// a session does
+    ///
+    /// ```
+    /// use solver::{rank_by_worst_case, scored_words, Ranking, Scorer};
+    ///
+    /// let words = scored_words(&["crane", "slate", "plate", "trace", "grace"]);
+    /// let ranking = Ranking {
+    ///     scorer: Scorer::Minimax,
+    ///     count_repeats: false,
+    ///     penalise_repeats: false,
+    ///     weights: None,
+    ///     bigrams: None,
+    ///     tie_seed: None,
+    /// };
+    /// let frequency = Ranking {
+    ///     scorer: Scorer::Frequency,
+    ///     ..ranking
+    /// };
+    /// // the chosen scorer decides the order, on top of the frequency scores
+    /// let order = |words: Vec<_>| words.into_iter().map(|sw: solver::ScoredWord| sw.word).collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     order(ranking.rank(words.clone(), &[])),
+    ///     order(rank_by_worst_case(frequency.rank(words, &[])))
+    /// );
+    /// ```
+    pub fn rank(&self, results: Vec<ScoredWord>, known_info: &[GuessResult]) -> Vec<ScoredWord> {
+        let mut words = self
+            .scorer
+            .rank(results, known_info, self.count_repeats, self.weights);
+        if self.penalise_repeats {
+            penalise_repeats(&mut words);
+        }
+        if let Some(bigrams) = &self.bigrams {
+            favour_common_bigrams(&mut words, known_info, bigrams);
+        }
+        if let Some(seed) = self.tie_seed {
+            self.shuffle_ties(&mut words, known_info, seed);
+        }
+        words
+    }
+
+    /// Shuffles each run of words that every step of the ranking scored the
+    /// same, so the seed only decides what would otherwise come down to
+    /// alphabetical order. The shuffle depends only on the seed and the clues,
+    /// so the same game gets the same order whichever way it's played.
+    fn shuffle_ties(&self, words: &mut [ScoredWord], known_info: &[GuessResult], seed: u64) {
+        let penalised = repeat_penalised_score(words.len());
+        let bigram = self
+            .bigrams
+            .as_ref()
+            .map(|bigrams| bigram_score(known_info, bigrams));
+        let keys: Vec<[f64; 4]> = words
+            .par_iter()
+            .map(|sw| {
+                [
+                    self.scorer.order_key(sw, words),
+                    sw.score as f64,
+                    if self.penalise_repeats {
+                        penalised(sw)
+                    } else {
+                        0.0
+                    },
+                    bigram.as_ref().map_or(0.0, |score| score(sw)),
+                ]
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(known_info.iter().fold(seed, |acc, clue| {
+            acc.wrapping_mul(243).wrapping_add(clue.code() as u64)
+        }));
+        let mut start = 0;
+        while start < words.len() {
+            let end = start
+                + keys[start..]
+                    .iter()
+                    .take_while(|key| **key == keys[start])
+                    .count();
+            words[start..end].shuffle(&mut rng);
+            start = end;
+        }
+    }
+}
+
+/// Why [`suggest`] couldn't rank the candidates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuggestError {
+    /// A word on the list isn't five letters long, so clues can't be worked
+    /// out for it
+    WrongLength(String),
+    /// A word on the list has letters outside ascii, which clues are worked
+    /// out byte by byte for
+    NotAscii(String),
+    /// A guess in the history isn't on the word list
+    NotInList(String),
+    /// A guess's result couldn't be read
+    Pattern(PatternError),
+}
+
+impl std::fmt::Display for SuggestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SuggestError::WrongLength(word) => write!(f, "{} isn't 5 letters long", word),
+            SuggestError::NotAscii(word) => write!(f, "{} has letters that aren't ascii", word),
+            SuggestError::NotInList(word) => write!(f, "{} isn't on the word list", word),
+            SuggestError::Pattern(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SuggestError {}
+
+/// Ranks the words still possible after `history`, each guess paired with its
+/// result written the way [`parse_pattern`] reads it. `Entropy` and `Minimax`
+/// put the words that split the rest best first, every other strategy puts the
+/// likeliest first. Nothing is read, printed or cached, so this is the whole
+/// solver for anyone embedding it.
+///
+/// ```
+/// use solver::{suggest, Strategy, SuggestError};
+///
+/// let words = ["crane", "slate", "plate", "trace"];
+/// let history = [("crane".to_string(), "bbgbg".to_string())];
+/// let ranked = suggest(&words, &history, Strategy::Entropy).unwrap();
+/// let mut left: Vec<&str> = ranked.iter().map(|sw| sw.word.as_str()).collect();
+/// left.sort();
+/// assert_eq!(left, ["plate", "slate"]);
+///
+/// let history = [("zzzzz".to_string(), "bbbbb".to_string())];
+/// assert_eq!(
+///     suggest(&words, &history, Strategy::Minimax).unwrap_err(),
+///     SuggestError::NotInList("zzzzz".to_string())
+/// );
+/// let history = [("crane".to_string(), "bbgb".to_string())];
+/// assert!(suggest(&words, &history, Strategy::Minimax).is_err());
+///
+/// // every word needs five tiles' worth of letters
+/// assert_eq!(
+///     suggest(&["crane", "slates"], &[], Strategy::Entropy).unwrap_err(),
+///     SuggestError::WrongLength("slates".to_string())
+/// );
+/// // and only ascii letters, so an accent isn't taken for an extra letter
+/// assert_eq!(
+///     suggest(&["crane", "crème"], &[], Strategy::Entropy).unwrap_err(),
+///     SuggestError::NotAscii("crème".to_string())
+/// );
+/// ```
+pub fn suggest(
+    words: &[&str],
+    history: &[(String, String)],
+    strategy: Strategy,
+) -> Result<Vec<ScoredWord>, SuggestError> {
+    if let Some(word) = words.iter().find(|word| !word.is_ascii()) {
+        return Err(SuggestError::NotAscii(word.to_string()));
+    }
+    if let Some(word) = words.iter().find(|word| word.len() != 5) {
+        return Err(SuggestError::WrongLength(word.to_string()));
+    }
+    let known_info = history
+        .iter()
+        .map(|(guess, pattern)| {
+            let guess = guess.to_lowercase();
+            if !words.contains(&guess.as_str()) {
+                return Err(SuggestError::NotInList(guess));
+            }
+            parse_pattern(&guess, pattern).map_err(SuggestError::Pattern)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let words = scored_words(words);
+    let scorer = match strategy {
+        Strategy::Entropy => Scorer::Entropy,
+        Strategy::Minimax => Scorer::Minimax,
+        _ => Scorer::Frequency,
+    };
+    Ok(scorer.rank(
+        filter_using_known_info(&words, &known_info),
+        &known_info,
+        false,
+        None,
+    ))
+}
+
+/// The code `calculate_guess_result` would give for `guess` against `answer`,
+/// from 0 to 242, worked out on the bytes without building the result. This is
+/// what the strategies bucket candidates by, so it's kept as cheap as it can be.
+///
+/// ```
+/// use solver::{calculate_guess_result, pattern_code, GuessResult};
+///
+/// let words = ["crane", "eerie", "speed", "abbey", "kebab", "geese", "three"];
+/// for guess in words {
+///     for answer in words {
+///         let result = calculate_guess_result(&answer.to_string(), &guess.to_string());
+///         let code = pattern_code(guess.as_bytes(), answer.as_bytes());
+///         assert_eq!(code, result.code());
+///         assert_eq!(GuessResult::from_code(code, guess).pattern(), result.pattern());
+///     }
+/// }
+///
+/// // every code comes back out of the result it describes
+/// for code in 0..243 {
+///     assert_eq!(GuessResult::from_code(code, "crane").code(), code);
+/// }
+/// ```
+pub fn pattern_code(guess: &[u8], answer: &[u8]) -> u8 {
+    let mut greens = [false; 5];
+    let mut unmatched = [None; 5];
+    for (i, (g, a)) in guess.iter().zip(answer).take(5).enumerate() {
+        if g == a {
+            greens[i] = true;
+        } else {
+            unmatched[i] = Some(*a);
+        }
+    }
+
+    let mut code = 0;
+    for (i, g) in guess.iter().take(5).enumerate() {
+        let digit = if greens[i] {
+            2
+        } else if let Some(copy) = unmatched.iter_mut().find(|a| **a == Some(*g)) {
+            *copy = None;
+            1
+        } else {
+            0
+        };
+        code = code * 3 + digit;
+    }
+    code
+}
+
+/// Calculates the result of a guess the way Wordle does, so a repeated letter
+/// is only yellow while the answer has copies of it that aren't already
+/// accounted for. Greens claim their copies first, then yellows go left to
+/// right, and any copies beyond what the answer has are red.
+pub fn calculate_guess_result(word: &String, guess: &String) -> GuessResult {
+    let mut result = [Character::Empty; 5];
+
+    // first pass: greens, keeping the answer's letters that are left unmatched.
+    // this runs for every pair of words when choosing openers, so it sticks to
+    // arrays rather than allocating
+    let mut unmatched = [None; 5];
+    for (i, (c, w)) in guess.chars().zip(word.chars()).enumerate() {
+        if w == c {
+            result[i] = Character::Green(c);
+        } else {
+            unmatched[i] = Some(w);
+        }
+    }
+
+    // second pass: yellows while unmatched copies remain, red otherwise
+    for (i, c) in guess.chars().enumerate() {
+        if matches!(result[i], Character::Green(_)) {
+            continue;
+        }
+        result[i] = match unmatched.iter_mut().find(|w| **w == Some(c)) {
+            Some(copy) => {
+                *copy = None;
+                Character::Yellow(c)
+            }
+            None => Character::Red(c),
+        };
+    }
+
+    GuessResult(result)
+}
@@ -1,606 +1,5903 @@
-use blake3::hash;
+mod tui;
+
+use blake3::{hash, Hasher};
 use bytecheck::CheckBytes;
+use clap::{Parser, Subcommand};
 use colored::Colorize;
 use directories::ProjectDirs;
-use rand::Rng;
+use log::{debug, info, warn};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use rayon::prelude::*;
-use rkyv::{Archive, Deserialize, Serialize};
+use rkyv::{AlignedVec, Archive, Deserialize, Serialize};
 use smooth::Smooth;
+use solver::{
+    anneal_opener, bigram_frequencies, calculate_guess_result, choose_next_guess,
+    expected_remaining, filter_using_known_info, get_first_guess_using, letter_bounds,
+    letter_index, matches_count, matches_tile, next_guess, parse_pattern, parse_pattern_with,
+    parse_share, partition, partition_entropy, pattern_code, positional_frequencies,
+    scored_positions, Character, ClueLetters, ClueMessage, ErrorMessage, GuessResult, NextGuess,
+    Palette, Ranking, RoundMessage, ScoredWord, Scorer, Strategy,
+};
 use spinoff::{spinners, Spinner};
 use std::{
-    collections::HashMap,
-    io::{Read, Write},
+    cell::OnceCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{IsTerminal, Read, Seek, Write},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 const WORDS: &str = include_str!("../../wordle/src/words.txt");
 
-struct GuessResult([Character; 5]);
+/// Most guesses Wordle allows, including the answer itself, both in a session
+/// and when the solver solves a word itself, unless `--max-guesses` says otherwise
+const MAX_GUESSES: usize = 6;
 
-impl std::fmt::Debug for GuessResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.0
-                .iter()
-                .map(|c| format!("{:?}", c))
-                .collect::<String>()
-        )
-    }
-}
+/// Whether slow work shows a spinner, turned off for `solve --explain` and
+/// `replay`
+static SPINNERS: AtomicBool = AtomicBool::new(true);
 
-#[derive(Debug, Clone)]
-struct ScoredWord {
-    word: String,
-    score: usize,
-}
+/// Set from `--quiet`, which leaves out spinners and progress along with them
+static QUIET: AtomicBool = AtomicBool::new(false);
 
-#[derive(Archive, Deserialize, Serialize)]
-#[archive_attr(derive(CheckBytes))]
-struct WordListCache {
-    strats: HashMap<Vec<u8>, (Strategy, String)>,
-}
+/// Changed whenever the way clues are worked out or read changes, so
+/// strategies and transition tables cached under the old rules aren't reused
+const CLUE_RULES_VERSION: u32 = 2;
 
-fn main() {
-    let cache_dir = ProjectDirs::from("com", "617a7a", "wordle")
-        .expect("Could not find config directory")
-        .cache_dir()
-        .to_str()
-        .expect("Could not convert config directory to string")
-        .to_string();
+/// Exit code used when the clues given rule out every word in the list, so
+/// scripts can tell contradictory input apart from a normal exit
+const EXIT_NO_MATCHES: i32 = 2;
 
-    // create the cache directory if it doesn't exist
-    std::fs::create_dir_all(&cache_dir).expect("Could not create cache directory");
+/// Written as the first byte of the strategies cache, and bumped whenever
+/// `WordListCache` changes shape so caches from older versions are thrown away
+/// and rebuilt instead of failing to load
+const CACHE_SCHEMA_VERSION: u8 = 4;
 
-    // open read-write, create if it doesn't exist at cache_dir/strategies
-    let mut cache_file = std::fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .open(format!("{}/strategies", cache_dir))
-        .expect("Could not open cache file");
+/// Exit code used by `solve` when the solver doesn't find the answer in time
+const EXIT_UNSOLVED: i32 = 3;
 
-    let mut bytes = vec![];
+/// Width to fall back on when it isn't given and can't be detected
+const DEFAULT_WIDTH: usize = 80;
+/// Spaces left between columns of candidates
+const COLUMN_GAP: usize = 2;
 
-    cache_file
-        .read_to_end(&mut bytes)
-        .expect("Could not read cache file");
+/// How many of the likeliest words each round suggests in a JSON session
+const JSON_SUGGESTIONS: usize = 10;
 
-    let cache: WordListCache = rkyv::from_bytes(&bytes).unwrap_or(WordListCache {
-        strats: HashMap::new(),
-    });
+/// How many rows of candidates `show` prints before waiting to go on
+const SHOW_PAGE_LINES: usize = 20;
 
-    let mut known_info: Vec<GuessResult> = vec![];
-    let mut words: Vec<ScoredWord> = WORDS
-        .split_whitespace()
-        .map(|s| ScoredWord {
-            word: s.to_string(),
-            score: 1,
-        })
-        .collect();
+/// A Wordle solver that picks its strategy by solving every word on the list
+#[derive(Parser)]
+struct Args {
+    #[command(subcommand)]
+    mode: Option<Mode>,
 
-    let words_digest = hash(WORDS.as_bytes());
+    /// Word list to solve with, one word per line, instead of the built-in one
+    #[arg(value_name = "WORDLIST")]
+    word_list: Option<String>,
 
-    let first_guess: String;
+    /// File of words that can be guessed but are never the answer, one per line.
+    /// Openers and probes can then come from these as well, while only the
+    /// word list is treated as possible answers
+    #[arg(long, value_name = "PATH")]
+    guesses: Option<String>,
 
-    if let Some(strat) = cache.strats.get(&words_digest.as_bytes().to_vec()) {
-        println!(
-            "Using {} strategy from cache at {}/strategies for wordset {}",
-            format!("{:?}", strat.0).magenta(),
-            cache_dir,
-            words_digest.to_hex().cyan()
-        );
-        first_guess = strat.1.clone();
-    } else {
-        println!(
-            "{}",
-            format!(
-                "No cached strategy found, generating one for wordset {}",
-                words_digest.to_hex()
-            )
-            .black()
-        );
-        let (strat, fw) = choose_optimal_strategy(&words);
+    /// Width to fit candidate lists to, detected from $COLUMNS if not given
+    #[arg(long, visible_alias = "max-words-display-width")]
+    width: Option<usize>,
 
-        let mut cache = cache;
-        cache
-            .strats
-            .insert(words_digest.as_bytes().to_vec(), (strat, fw.clone()));
+    /// Print how many candidates each tile of the latest clue eliminated
+    #[arg(long)]
+    explain_filter: bool,
 
-        first_guess = fw;
+    /// Show how many words each suggestion would leave on average. Working it
+    /// out compares every suggestion with every candidate, so it's slow early on
+    #[arg(long)]
+    expected_remaining: bool,
 
-        cache_file
-            .write_all(
-                &rkyv::to_bytes::<WordListCache, 4096>(&cache).expect("Could not serialise cache"),
-            )
-            .expect("Could not write to cache file");
-        println!(
-            "{}",
-            format!("Cached strategy in {}/strategies", cache_dir).black()
-        );
-    }
+    /// Break ties between words the ranking can't tell apart randomly, but
+    /// reproducibly from this seed and the clues, instead of alphabetically
+    #[arg(long)]
+    tie_seed: Option<u64>,
 
-    let mut last_guess = first_guess.clone();
+    /// How many guesses, including the answer, a word has to be solved in, for
+    /// variants with more or fewer than Wordle's 6. Used by sessions and when
+    /// testing strategies
+    #[arg(long, value_name = "N", value_parser = parse_guess_budget, global = true)]
+    max_guesses: Option<usize>,
 
-    for i in 0..5 {
-        if i == 0 {
-            println!("\n{}", "TIPS:".bold());
-            println!(" - Leave a field empty to autofill all empty letters with that colour");
-            println!(" - If you type less than 5 letters, we'll replace the rest with dashes");
+    /// Seed every random choice from this, such as the `random` strategy's
+    /// opener or which words `analyze --sample` picks, so runs can be repeated
+    #[arg(long, global = true)]
+    seed: Option<u64>,
 
-            // for our first guess, we have no information, so we just guess the word
-            // not as an actual word, but as the top 5 letters in the word list by frequency
-            println!("\nFirst guess is {}!", first_guess.blue());
-        } else {
-            // after the first guess, we get input from the user which we can use to refine
-            // our guess
-            let guess_result = get_guess_result(&last_guess);
-            known_info.push(guess_result);
-            let start = std::time::Instant::now();
-            let filtered_results = filter_using_known_info(&words, &known_info);
-            let elapsed = start.elapsed();
-            let total_chars = filtered_results.iter().map(|s| s.word.len()).sum::<usize>();
+    /// File of `word count` lines giving how often each word is the answer, used
+    /// to weight the letter frequencies openers are built from
+    #[arg(long, value_name = "PATH")]
+    answer_frequencies: Option<String>,
 
-            println!(
-                "\n{} Found {} possible {}",
-                format!(
-                    "[{:?}, {} char/s]",
-                    elapsed,
-                    (total_chars as f64 / start.elapsed().as_secs_f64()).smooth_str()
-                )
-                .black(),
-                filtered_results.len(),
-                if filtered_results.len() == 1 {
-                    "word"
-                } else {
-                    "words"
-                },
-            );
-            let start = std::time::Instant::now();
-            words = optimise_results(filtered_results, &known_info);
-            let elapsed = start.elapsed();
-            println!(
-                "{} Scored & reordered results",
-                format!(
-                    "[{:?}, {} char/s]",
-                    elapsed,
-                    (total_chars as f64 / start.elapsed().as_secs_f64()).smooth_str()
-                )
-                .black(),
-            );
+    /// File of words to restrict the candidates to, e.g. only common words. Only
+    /// words that are also in the main list are used
+    #[arg(long, value_name = "PATH")]
+    only: Option<String>,
 
-            last_guess = words[0].word.clone();
+    /// Cut clue input that's too long down to size, with a warning, rather than
+    /// asking for it again
+    #[arg(long)]
+    truncate_long: bool,
 
-            let total_score = words.par_iter().map(|sw| sw.score).sum::<usize>();
+    /// The letters you type for green, yellow and grey tiles when giving a
+    /// result as a pattern, in that order
+    #[arg(long, value_name = "GYB", default_value = "gyb", value_parser = parse_colors)]
+    colors: ClueLetters,
 
-            if words.len() < 5 || i == 4 {
-                let fmttd_list = words
-                    .iter()
-                    .map(|sw| {
-                        format!(
-                            "  - {} ({}%)",
-                            sw.word.blue(),
-                            (100.0 * (sw.score as f64) / (total_score as f64)).smooth_str()
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\n");
-                println!("Try one of these: \n{}", fmttd_list);
-            } else {
-                let sw = &words[0];
-                println!(
-                    "Try {} ({}%)",
-                    sw.word.blue(),
-                    (100.0 * (sw.score as f64) / (total_score as f64)).smooth_str()
-                );
-            }
-        }
-        if i != 4 {
-            println!("\n{}", format!("Guess {} of 5", i + 1).black());
-        }
-    }
+    /// Stop testing strategies after this many seconds, using the best one found
+    /// so far. A strategy picked this way isn't cached, since it may not be the best
+    #[arg(long, value_name = "SECONDS")]
+    deadline: Option<f64>,
+
+    /// Narrate each of the solver's choices: how many words were left, and how
+    /// the suggested word scored
+    #[arg(long)]
+    teach: bool,
+
+    /// Also save the `--teach` narration to this file
+    #[arg(long, value_name = "PATH")]
+    teach_file: Option<String>,
+
+    /// Rank words with repeated letters lower while many words remain, since they
+    /// test fewer letters. The penalty fades out as the candidates narrow
+    #[arg(long)]
+    penalise_repeats: bool,
+
+    /// Between words that score about the same, suggest those made of letter
+    /// pairs common in the word list first, e.g. "groan" before "grown". Only
+    /// the order changes, never which words are left
+    #[arg(long, conflicts_with = "penalise_repeats")]
+    bigrams: bool,
+
+    /// Score a repeated letter in every position it fills when ranking
+    /// suggestions, instead of only once
+    #[arg(long)]
+    count_repeats: bool,
+
+    /// How to rank suggestions after each clue: frequency, entropy or minimax.
+    /// A session can switch with `strategy <name>` as it goes. Solves, analysis
+    /// and strategy testing rank the same way
+    #[arg(long, value_parser = parse_scorer, default_value = "frequency")]
+    scorer: Scorer,
+
+    /// Search the word list, and `--guesses` if given, for an opener that splits
+    /// the words well instead of testing every strategy, which is much faster on
+    /// huge lists. Stops at `--deadline` if given
+    #[arg(long)]
+    approx_opener: bool,
+
+    /// Use this strategy instead of testing every one to pick the best, which
+    /// is much faster on huge lists: frequency-simple, frequency-position-aware,
+    /// random, entropy, minimax or frequency-positional-word
+    #[arg(long, value_parser = parse_strategy, conflicts_with = "approx_opener")]
+    strategy: Option<Strategy>,
+
+    /// Two openers, like `slate,chord`, to play one after the other whatever
+    /// their clues, before the solver starts adapting. In hard mode the second
+    /// is only played if it fits the first's clue. Used by sessions and the
+    /// `solvable`, `solve`, `analyze` and `bench` modes
+    #[arg(long, value_name = "FIRST,SECOND", value_parser = parse_opener)]
+    opener: Option<(String, String)>,
+
+    /// Write the solve up as Markdown to this file, for sessions and `solvable`
+    #[arg(long, value_name = "PATH", global = true)]
+    md: Option<String>,
+
+    /// Show more diagnostics, such as timings. Repeat for even more
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Only print results: no spinner or progress while a strategy is chosen,
+    /// and no diagnostics short of warnings. Logs and CI get this by default
+    /// for the spinner, since stdout isn't a terminal there
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Play by hard mode rules, where every guess has to fit the clues so far.
+    /// Suggestions and strategy testing both stick to them
+    #[arg(long, global = true)]
+    hard: bool,
+
+    /// Print machine-readable JSON instead of text, where supported. A session
+    /// is then driven by a line of JSON per clue, answered by a line per round
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Draw tiles in orange and blue instead of green and yellow. The palette
+    /// can also be picked with `WORDLE_PALETTE=high-contrast`
+    #[arg(long, global = true)]
+    colorblind: bool,
+
+    /// Play the session full screen, picking each tile's colour with the arrow
+    /// keys or the mouse instead of typing it
+    #[arg(long)]
+    tui: bool,
+
+    /// Read the clues so far from stdin, one `crane gybrr` line per guess, and
+    /// print just the next guess. Nothing is printed if the clues rule out every
+    /// word, and the solver exits with code 2
+    #[arg(long, visible_alias = "plain", conflicts_with_all = ["json", "tui"])]
+    batch: bool,
 }
 
-#[derive(Clone, Copy)]
-enum Character {
-    /// The character is in the word, but not in the correct position
-    Yellow(char),
-    /// The character is in the word, and in the correct position
-    Green(char),
-    /// The character is not in the word
-    Red(char),
-    /// Used only as a placeholder during user input
-    Empty,
+/// Things the solver can do instead of an interactive session
+#[derive(Subcommand)]
+enum Mode {
+    /// Check whether the solver finds a word within the guess budget, and show how
+    Solvable { word: String },
+    /// Play the solver against a known answer, printing each guess and its clue
+    Solve {
+        #[arg(long)]
+        answer: String,
+        /// Say after each guess why it was played: how many words it was picked
+        /// from, how much its clue narrowed them, and the best ones passed over
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Replay a game from the squares the NYT shares, copied to the clipboard,
+    /// showing what the solver would have played each round. Put the word
+    /// guessed after each row, or list the words on their own lines
+    Assist {
+        /// Read the shared game from stdin instead of the clipboard
+        #[arg(long)]
+        stdin: bool,
+    },
+    /// Replay a logged game, one guess and its result per line like `crane
+    /// gybrr`, showing the words left and what the solver would play after each
+    Replay { path: String },
+    /// Solve every word on the list with the chosen strategy and report how it did
+    Analyze {
+        /// Write each word's guess count and whether it was solved to a CSV file
+        #[arg(long, value_name = "PATH")]
+        export_stats: Option<String>,
+        /// Only solve this many randomly picked words, estimating how the whole
+        /// list would do
+        #[arg(long, value_name = "N")]
+        sample: Option<usize>,
+    },
+    /// Time solving every word on the list and report the guess counts
+    Bench {
+        /// Strategy to benchmark, instead of the chosen one: frequency-simple,
+        /// frequency-position-aware, random, entropy, minimax or
+        /// frequency-positional-word
+        #[arg(long, value_parser = parse_strategy)]
+        strategy: Option<Strategy>,
+    },
+    /// Print the solver's second guess for the most common clues after an opener
+    CheatSheet {
+        /// Opener to build the sheet for, instead of the chosen strategy's
+        #[arg(long)]
+        opener: Option<String>,
+        /// How many of the most common clues to include
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Print the chosen strategy's opener and nothing else, working it out and
+    /// caching it first if it isn't cached
+    Opener,
+    /// Print a checkable JSON claim of how well the chosen opener does. Its
+    /// checksum only catches accidental edits, as anyone can recompute it
+    Claim,
+    /// Recompute a claim made by `claim` and check it holds for this word list
+    VerifyClaim { path: String },
+    /// Show how uncertain the letter in each position is across the word list
+    EntropyProfile,
+    /// Compare openers read from a file, one per line, best first
+    EvalOpeners { path: String },
+    /// Find two openers that between them test the most common letters
+    BestPair {
+        /// How many of the best single openers to pair up
+        #[arg(long, default_value_t = 50)]
+        top: usize,
+    },
+    /// Show the chance of solving within each number of guesses from an opener
+    SolveCdf {
+        /// Opener to use, instead of the chosen strategy's
+        #[arg(long)]
+        opener: Option<String>,
+    },
+    /// Report letters and letter positions the word list has few or no words for
+    Coverage {
+        /// Flag positions with fewer words than this having the letter there
+        #[arg(long, default_value_t = 3)]
+        min: usize,
+    },
+    /// Print solver sessions for randomly picked answers as JSON, so changes to
+    /// the solver's suggestions can be spotted by replaying them
+    #[command(hide = true)]
+    GenFixtures {
+        /// Seed for picking the answers, so the same fixtures come out every time
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// How many sessions to generate
+        #[arg(long, default_value_t = 20)]
+        count: usize,
+    },
+    /// List or remove the strategies cached for each word list
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
 }
 
-impl std::fmt::Debug for Character {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Character::Yellow(c) => write!(f, "{}", c.to_string().yellow()),
-            Character::Green(c) => write!(f, "{}", c.to_string().green()),
-            Character::Red(c) => write!(f, "{}", c.to_string().red()),
-            Character::Empty => write!(f, "{}", "-".blue()),
-        }
-    }
+/// What `cache` does with the cached strategies
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Print each word list's digest, its strategy and opener, and how it did
+    List,
+    /// Remove everything cached for the word list whose digest starts with
+    /// this, or the whole cache if none is given
+    Clear { digest: Option<String> },
 }
 
-/// Filters a wordlist based on previous guess results
-fn filter_using_known_info(
-    words: &Vec<ScoredWord>,
-    known_info: &Vec<GuessResult>,
-) -> Vec<ScoredWord> {
-    // we have a list of words, and we know some information about the word we're
-    // looking for we process the words finding possible words that match
-    // **all** the known information
-    words
-        .iter()
-        .filter(|sw| {
-            known_info.iter().all(|guess| {
-                guess.0.iter().enumerate().all(|(i, c)| match c {
-                    // word contains all yellow characters
-                    Character::Yellow(t) => {
-                        sw.word.contains(*t) && sw.word.chars().nth(i).unwrap() != *t
-                    }
-                    // word contains all green characters in the correct position
-                    Character::Green(t) => sw.word.chars().nth(i).unwrap() == *t,
-                    // word doesn't contain any red characters
-                    Character::Red(t) => !sw.word.contains(*t),
-                    Character::Empty => unreachable!("Empty character in known_info"),
-                })
-            })
-        })
-        .map(|sw| sw.clone())
-        .collect()
+/// What can stop the solver, from a cache it can't find to input it can't use
+#[derive(thiserror::Error)]
+enum SolverError {
+    /// A file or text given to the solver can't be used, and why
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0} is not in the word list")]
+    NotInWordList(String),
+    #[error("could not read the clipboard: {0}")]
+    Clipboard(String),
+    #[error("best-pair needs at least two words on the list")]
+    NoPair,
+    #[error("--tui needs a terminal to draw on")]
+    NoTerminal,
+    #[error("could not draw the TUI: {0}")]
+    Tui(std::io::Error),
+    #[error("nothing is cached for a digest starting with {0}")]
+    NothingCached(String),
+    #[error("could not find a cache directory for this user")]
+    NoCacheDir,
+    #[error("the cache directory {0} isn't valid UTF-8")]
+    CacheDirNotUnicode(std::path::PathBuf),
+    #[error("could not {action} {path}: {source}")]
+    Io {
+        action: &'static str,
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("could not serialise {what}: {reason}")]
+    Serialise { what: &'static str, reason: String },
+    /// Only ever costs the cached work, which is rebuilt
+    #[error("the cache is from another version of the solver")]
+    OutdatedCache,
+    /// Only ever costs the cached work, which is rebuilt
+    #[error("the cache couldn't be read")]
+    CorruptCache,
 }
 
-/// reorders a wordlist to optimise the next guess using the strategy
-fn optimise_results(results: Vec<ScoredWord>, known_info: &Vec<GuessResult>) -> Vec<ScoredWord> {
-    // if the length is 0, no optimisation is required
-    if results.len() == 0 {
-        return results;
-    }
-
-    // at this stage, the filter has ensured that any red characters are not in the
-    // word, and all green characters are already in their correct positions.
-    // we therefore score based upon the yellow characters exclusively,
-    // so the list of results is sorted to lower the maximum guesses to find the
-    // word
-
-    // this is done by scoring each word based on the frequency of the yellow
-    // characters
-
-    // example: we make these two guesses:
-    // [Red(D), Green(R), Red(U), Yellow(N), Red(K)]
-    // [Red(F), Red(I), Yellow(G), Red(H), Red(T)]
-    // which narrows the wordlist down to:
-    // groan, green, grown
-
-    // we can identify that the first character has to be 'g', the second is 'r' and
-    // the last is 'n' the differences between the words are therefore the third
-    // and fourth characters:   'o' and 'e'
-    // for position 3, 'o' is the most common character, so words with 'o' in
-    // position 3 are more likely to be the word than words with 'e' in position
-    // 3 "grown" and "groan" are equally likely to be the word, as their uniqueness
-    // is the same, but "green" is less likely
-
-    // ALGORITHM:
-    // 1. count the frequency of each character in each position using a
-    //    [[usize; 26]; 5], ensuring to ignore any green or red characters
-    // 2. score each word based on the frequency of the yellow characters
-    // 3. sort the words by their score
-
-    let frequencies: [[usize; 26]; 5] = results.iter().fold(
-        [[0; 26], [0; 26], [0; 26], [0; 26], [0; 26]],
-        |mut acc, sw| {
-            for (i, c) in sw.word.chars().enumerate() {
-                acc[i][c as usize - 97] += 1;
-            }
-            acc
-        },
-    );
+impl SolverError {
+    /// Wraps an IO error with what was being done to which file
+    fn io<'a>(
+        action: &'static str,
+        path: &'a str,
+    ) -> impl FnOnce(std::io::Error) -> SolverError + 'a {
+        move |source| SolverError::Io {
+            action,
+            path: path.to_string(),
+            source,
+        }
+    }
 
-    let mut scored_words = results
-        .par_iter()
-        .map(|sw| {
-            let mut score = 1;
-            for (i, c) in sw.word.chars().enumerate() {
-                // if all the known info for this position is yellow, we can score
-                if known_info
-                    .iter()
-                    .all(|guess| matches!(guess.0[i], Character::Yellow(_)))
-                {
-                    score += frequencies[i][c as usize - 97];
-                }
-            }
-            ScoredWord {
-                word: sw.word.clone(),
-                score,
-            }
-        })
-        .collect::<Vec<ScoredWord>>();
-    scored_words.sort_by(|a, b| b.score.cmp(&a.score));
-    scored_words
+    /// Wraps a serialisation error with what was being serialised
+    fn serialise<E: std::fmt::Display>(what: &'static str) -> impl FnOnce(E) -> SolverError {
+        move |e| SolverError::Serialise {
+            what,
+            reason: e.to_string(),
+        }
+    }
+}
+
+// `main` returning an error prints it with `Debug`, which should read the same as
+// the message rather than show the variant
+impl std::fmt::Debug for SolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Archive, Deserialize, Serialize)]
+#[derive(Default, Archive, Deserialize, Serialize)]
 #[archive_attr(derive(CheckBytes))]
-enum Strategy {
-    FrequencySimple,
-    FrequencyPositionAware,
-    Random,
-}
-
-/// Returns the optimal starting guess for the wordset
-fn get_first_guess(words: &Vec<ScoredWord>, strategy: Strategy) -> String {
-    match strategy {
-        Strategy::FrequencyPositionAware => {
-            // our first guess is constructed off the most common character in each position
-            let frequencies: [[usize; 26]; 5] = words.iter().fold(
-                [[0; 26], [0; 26], [0; 26], [0; 26], [0; 26]],
-                |mut acc, sw| {
-                    for (i, c) in sw.word.chars().enumerate() {
-                        acc[i][c as usize - 97] += 1;
-                    }
-                    acc
-                },
-            );
+struct WordListCache {
+    strats: HashMap<StrategyKey, CachedStrategy>,
+    /// Each opener's transition table, for every wordset it's been played on
+    transitions: HashMap<TransitionsKey, OpenerTransitions>,
+    /// The best opener pair found by `best-pair`, along with how many of the top
+    /// openers it searched
+    pairs: HashMap<Vec<u8>, (usize, String, String)>,
+    /// How often each letter is in each position across the whole list, which
+    /// strategies open from
+    frequencies: HashMap<Vec<u8>, [[usize; 26]; 5]>,
+}
 
-            // find the most likely character in each position
-            let mut guess = String::new();
-            for freq in frequencies.iter() {
-                let mut max = 0;
-                let mut max_index = 0;
-                for (i, count) in freq.iter().enumerate() {
-                    if *count > max {
-                        max = *count;
-                        max_index = i;
-                    }
-                }
-                guess.push((max_index + 97) as u8 as char);
-            }
+/// What a cached strategy was chosen for: the wordset, and the flags that can
+/// change which strategy or opener wins
+#[derive(Clone, PartialEq, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes, PartialEq, Eq, Hash))]
+struct StrategyKey {
+    digest: Vec<u8>,
+    /// A strategy asked for by name is kept apart from the chosen one, so
+    /// neither is mistaken for the other
+    strategy: Option<Strategy>,
+    /// A seed can change a random opener
+    seed: Option<u64>,
+    /// The best strategy for one budget needn't be the best for another
+    max_guesses: Option<usize>,
+}
+
+/// What a transition table was built for: the wordset, and the opener played
+/// on it
+#[derive(Clone, PartialEq, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes, PartialEq, Eq, Hash))]
+struct TransitionsKey {
+    digest: Vec<u8>,
+    opener: String,
+}
 
-            return guess;
+impl StrategyKey {
+    /// The flags the strategy was picked with, like ` --seed=1`, each with a
+    /// space before it
+    fn flags(&self) -> String {
+        let mut flags = String::new();
+        if let Some(strategy) = self.strategy {
+            flags.push_str(&format!(" --strategy={:?}", strategy));
         }
-        Strategy::FrequencySimple => {
-            // count all characters and take the top 5
-            let mut char_counts: [usize; 26] = [0; 26];
-            for sw in words {
-                for c in sw.word.chars() {
-                    char_counts[c as usize - 97] += 1;
-                }
-            }
+        if let Some(seed) = self.seed {
+            flags.push_str(&format!(" --seed={}", seed));
+        }
+        if let Some(n) = self.max_guesses {
+            flags.push_str(&format!(" --max-guesses={}", n));
+        }
+        flags
+    }
+}
 
-            // sort by count
-            let mut char_counts: Vec<(usize, char)> = char_counts
-                .iter()
-                .enumerate()
-                .map(|(i, count)| (*count, (i + 97) as u8 as char))
-                .collect();
-            char_counts.sort_by(|a, b| b.0.cmp(&a.0));
+/// The strategy chosen for a wordset, and how well it did when it was chosen
+#[derive(Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes))]
+struct CachedStrategy {
+    strategy: Strategy,
+    first_guess: String,
+    /// How many words were solvable and the average guesses, or `None` if the
+    /// strategy was never tested, like an approximate opener or a strategy
+    /// given with `--strategy`
+    stats: Option<(u32, f64)>,
+    /// When the strategy was chosen, in seconds since the Unix epoch
+    computed_at: u64,
+}
 
-            // take the top 5
-            let mut guess = String::new();
-            for (_, c) in char_counts.iter().take(5) {
-                guess.push(*c);
-            }
+/// The candidates left after each of the 243 possible clues for the opener,
+/// precomputed so the first round of a session is a lookup
+#[derive(Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes))]
+struct OpenerTransitions {
+    opener: String,
+    /// Indices into the wordlist of the words left for each pattern code
+    candidates: HashMap<u8, Vec<u32>>,
+}
 
-            return guess;
+impl OpenerTransitions {
+    /// Buckets every word by the clue the opener would get if it were the answer
+    fn build(opener: &String, words: &[ScoredWord]) -> Self {
+        let mut candidates: HashMap<u8, Vec<u32>> = HashMap::new();
+        for (i, sw) in words.iter().enumerate() {
+            let code = pattern_code(opener.as_bytes(), sw.word.as_bytes());
+            candidates.entry(code).or_default().push(i as u32);
         }
-        Strategy::Random => {
-            // create 5 random characters
-            let mut rng = rand::thread_rng();
-            let mut guess = String::new();
-            for _ in 0..5 {
-                guess.push(rng.gen_range('a'..='z'));
-            }
-            return guess;
+        OpenerTransitions {
+            opener: opener.clone(),
+            candidates,
+        }
+    }
+
+    /// Returns the remaining candidates if the only clue so far is for the
+    /// opener, or `None` if the table can't answer for the known info
+    fn lookup(&self, known_info: &[GuessResult], words: &[ScoredWord]) -> Option<Vec<ScoredWord>> {
+        let [guess] = known_info else {
+            return None;
+        };
+        // the table only covers the opener's letters, and wildcards aren't patterns
+        // the opener can actually get
+        let is_opener = guess
+            .0
+            .iter()
+            .zip(self.opener.chars())
+            .all(|(c, o)| match c {
+                Character::Green(t) | Character::Yellow(t) | Character::Red(t) => *t == o,
+                Character::Wild | Character::Empty => false,
+            });
+        if !is_opener {
+            return None;
         }
+
+        Some(
+            self.candidates
+                .get(&guess.code())
+                .map(|indices| indices.iter().map(|i| words[*i as usize].clone()).collect())
+                .unwrap_or_default(),
+        )
     }
 }
 
-/// Handles user input for a guess result
-fn get_guess_result(last_guess: &String) -> GuessResult {
-    let mut buffer = GuessResult([Character::Empty; 5]);
+/// The clue code every word would get against every other word on the list,
+/// so solving the whole list looks clues up instead of working them out. With
+/// one byte per pair it's far too big to load with the strategies on every run,
+/// so it's cached in a file of its own and only loaded when it's needed.
+#[derive(Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes))]
+struct PatternMatrix {
+    /// The wordset digest the matrix was built for
+    digest: Vec<u8>,
+    /// The code for guessing word `g` of the guesses when the answer is word
+    /// `a` is at `g * words.len() + a`
+    codes: Vec<u8>,
+}
 
-    for t in ["yellow", "red", "green"] {
-        if buffer.0.iter().all(|c| !matches!(c, Character::Empty)) {
-            break;
-        } else if t == "green" {
-            let last_guess: Vec<char> = last_guess.chars().collect();
-            // replace all empty characters with green characters from the previous guess
-            for (i, c) in buffer.0.iter_mut().enumerate() {
-                if matches!(c, Character::Empty) {
-                    *c = Character::Green(last_guess[i]);
+impl PatternMatrix {
+    fn build(digest: &[u8], words: &[ScoredWord], guesses: &[ScoredWord]) -> Self {
+        let mut codes = vec![0; guesses.len() * words.len()];
+        codes
+            .par_chunks_mut(words.len().max(1))
+            .zip(guesses)
+            .for_each(|(row, guess)| {
+                for (code, answer) in row.iter_mut().zip(words) {
+                    *code = pattern_code(guess.word.as_bytes(), answer.word.as_bytes());
                 }
-            }
-            break;
+            });
+        PatternMatrix {
+            digest: digest.to_vec(),
+            codes,
         }
-        println!(
-            "Enter the {t} characters. For non-{t} characters, use '-':",
-            t = match t {
-                "yellow" => "yellow".yellow(),
-                "red" => "red".red(),
-                "green" => "green".green(),
-                _ => unreachable!(),
-            }
-        );
-        let input = read_line(5, last_guess);
+    }
+}
 
-        if input.len() == 0 {
-            // special case for empty input, we assume all empty characters are of the given
-            // type
-            for (i, c) in buffer.0.iter_mut().enumerate() {
-                if matches!(c, Character::Empty) {
-                    let c2 = last_guess.chars().nth(i).unwrap();
-                    *c = match t {
-                        "yellow" => Character::Yellow(c2),
-                        "red" => Character::Red(c2),
-                        "green" => Character::Green(c2),
-                        _ => unreachable!(),
-                    }
-                }
-            }
-        } else {
-            for (i, c) in input.chars().enumerate() {
-                if c != '-' {
-                    buffer.0[i] = match t {
-                        "yellow" => Character::Yellow(c),
-                        "red" => Character::Red(c),
-                        "green" => Character::Green(c),
-                        _ => unreachable!(),
+/// Filters candidates by looking up clue codes in a `PatternMatrix`
+struct Patterns {
+    matrix: PatternMatrix,
+    /// Where each word is in the guesses the matrix was built for. The answers
+    /// come first, so an answer's index is also where it is in each row
+    index: HashMap<String, usize>,
+    /// How many of the guesses are answers, and so how long each row is
+    answers: usize,
+}
+
+impl Patterns {
+    /// Reads the matrix for this wordset from the cache at `path`, building and
+    /// caching it first if it's missing or for another wordset
+    fn load(path: &str, digest: &[u8], words: &[ScoredWord], guesses: &[ScoredWord]) -> Self {
+        let cached = std::fs::read(path)
+            .ok()
+            .and_then(|bytes| rkyv::from_bytes::<PatternMatrix>(&bytes).ok())
+            .filter(|matrix| matrix.digest == digest);
+        let matrix = match cached {
+            Some(matrix) => matrix,
+            None => {
+                let sp = Progress::start("Precomputing clues for every pair of words");
+                let start = Instant::now();
+                let matrix = PatternMatrix::build(digest, words, guesses);
+                // the matrix works just as well from memory, so failing to cache
+                // it only costs building it again next time
+                let saved = rkyv::to_bytes::<PatternMatrix, 4096>(&matrix)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| std::fs::write(path, bytes).map_err(|e| e.to_string()));
+                match saved {
+                    Ok(()) => sp.info(&format!(
+                        "{} Cached clues for {} pairs of words in {}",
+                        format!("[{:?}]", start.elapsed()).black(),
+                        matrix.codes.len(),
+                        path
+                    )),
+                    Err(e) => {
+                        sp.info(&format!(
+                            "{} Precomputed clues for {} pairs of words",
+                            format!("[{:?}]", start.elapsed()).black(),
+                            matrix.codes.len()
+                        ));
+                        warn!("Could not cache the clues in {}: {}", path, e);
                     }
                 }
+                matrix
             }
-        }
+        };
+        Patterns::new(matrix, words, guesses)
     }
 
-    print!("You have entered {:?}. Correct? (y): ", buffer);
-    let mut key = String::new();
-    std::io::stdout().flush().unwrap();
-    std::io::stdin().read_line(&mut key).unwrap();
-    key = key.trim().to_string();
-
-    if key == "y" || key == "" {
-        buffer
-    } else {
-        get_guess_result(last_guess)
+    /// Looks up clues in `matrix`, which was built for `words` and `guesses`,
+    /// with `words` at the start of `guesses`
+    fn new(matrix: PatternMatrix, words: &[ScoredWord], guesses: &[ScoredWord]) -> Self {
+        let index = guesses
+            .iter()
+            .enumerate()
+            .map(|(i, sw)| (sw.word.clone(), i))
+            .collect();
+        Patterns {
+            matrix,
+            index,
+            answers: words.len(),
+        }
     }
-}
 
-/// Reads a line from stdin, and returns it as a String. If the line is not the
-/// expected length, the user is prompted to try again.
-fn read_line(expected_length: usize, guess: &String) -> String {
-    let mut buffer = String::new();
-    print!(">> ");
-    std::io::stdout().flush().unwrap();
-    std::io::stdin().read_line(&mut buffer).unwrap();
-    buffer = buffer.trim().to_string();
+    /// The codes `guess` gets against every word on the list, and the one it
+    /// gets against `answer`, or `None` if either isn't on the list
+    fn row(&self, guess: &str, answer: &str) -> Option<(&[u8], u8)> {
+        let (guess, answer) = (*self.index.get(guess)?, *self.index.get(answer)?);
+        let len = self.answers;
+        if answer >= len {
+            return None;
+        }
+        let row = &self.matrix.codes[guess * len..(guess + 1) * len];
+        Some((row, row[answer]))
+    }
 
-    if buffer == "exit" {
-        println!("Exiting...");
-        std::process::exit(0);
+    /// Keeps the words on the list the matrix was built for that `guess` would
+    /// get the same clue against as it does against `answer`, or `None` if either
+    /// isn't on the list. Going through the list in order, each word's row entry
+    /// is just where it is, so nothing needs looking up.
+    fn filter_list(
+        &self,
+        words: &[ScoredWord],
+        guess: &str,
+        answer: &str,
+    ) -> Option<Vec<ScoredWord>> {
+        let (row, code) = self.row(guess, answer)?;
+        Some(
+            words
+                .iter()
+                .zip(row)
+                .filter(|(_, c)| **c == code)
+                .map(|(sw, _)| sw.clone())
+                .collect(),
+        )
     }
 
-    // ensure string is lowercase a-z or -
-    if !buffer.chars().all(|c| matches!(c, 'a'..='z' | '-')) {
-        println!("Please enter only lowercase letters or '-'.");
-        read_line(expected_length, guess)
-    } else if buffer.len() != expected_length {
-        if buffer.len() == 0 {
-            buffer
-        } else if buffer.len() < expected_length {
-            buffer.push_str(&"-".repeat(expected_length - buffer.len()));
-            buffer
-        } else {
-            println!("Please enter exactly {} characters.", expected_length);
-            read_line(expected_length, guess)
-        }
-    } else {
-        buffer
+    /// Like `filter_list`, but for any candidates from the list
+    fn filter(
+        &self,
+        candidates: &[ScoredWord],
+        guess: &str,
+        answer: &str,
+    ) -> Option<Vec<ScoredWord>> {
+        let (row, code) = self.row(guess, answer)?;
+        Some(
+            candidates
+                .iter()
+                .filter(
+                    |sw| matches!(self.index.get(&sw.word), Some(i) if row.get(*i) == Some(&code)),
+                )
+                .cloned()
+                .collect(),
+        )
     }
 }
 
-/// Calculates the result of a guess.
-fn calculate_guess_result(word: &String, guess: &String) -> GuessResult {
-    let mut result = [Character::Empty; 5];
-    for (i, c) in guess.chars().enumerate() {
-        if word.contains(c) {
-            if word.chars().nth(i).unwrap() == c {
-                result[i] = Character::Green(c);
-            } else {
-                result[i] = Character::Yellow(c);
-            }
-        } else {
-            result[i] = Character::Red(c);
-        }
+fn main() -> Result<(), SolverError> {
+    let args = Args::parse();
+    init_logging(args.verbose, args.quiet);
+    QUIET.store(args.quiet, Ordering::Relaxed);
+    // escape codes are just noise when piped, or when asked not to use them
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+        || !std::io::stdout().is_terminal()
+        || args.json
+    {
+        colored::control::set_override(false);
+    }
+    // how many guesses a word has to be solved in, including the answer itself
+    let max_guesses = args.max_guesses.unwrap_or(MAX_GUESSES);
+    // these are meant to be read through, so nothing should redraw over them
+    // while a strategy is chosen
+    if matches!(
+        args.mode,
+        Some(Mode::Solve { explain: true, .. } | Mode::Replay { .. } | Mode::Opener)
+    ) {
+        SPINNERS.store(false, Ordering::Relaxed);
     }
+    let palette = if args.colorblind {
+        Palette::HighContrast
+    } else {
+        match std::env::var("WORDLE_PALETTE") {
+            Ok(name) => Palette::parse(&name).unwrap_or_else(|| {
+                warn!(
+                    "Unknown palette {}, expected standard or high-contrast",
+                    name
+                );
+                Palette::Standard
+            }),
+            Err(_) => Palette::Standard,
+        }
+    };
 
-    GuessResult(result)
-}
+    let dirs = ProjectDirs::from("com", "617a7a", "wordle").ok_or(SolverError::NoCacheDir)?;
+    let cache_dir = dirs
+        .cache_dir()
+        .to_str()
+        .ok_or_else(|| SolverError::CacheDirNotUnicode(dirs.cache_dir().to_path_buf()))?
+        .to_string();
+    let cache_path = format!("{}/strategies", cache_dir);
 
-/// returns the number of words solvable within 5 guesses with the given
-/// strategy
-fn test_strategy(words: &Vec<ScoredWord>, strategy: Strategy) -> (i32, String) {
-    let guess = get_first_guess(words, strategy);
-    let solvable = words
-        .par_iter()
-        .map(|sw| {
-            let mut possible_words = words.clone();
-            let mut guess = guess.clone();
-            let mut guesses = 5;
-            let mut known_info = vec![];
-            loop {
-                let result = calculate_guess_result(&sw.word, &guess);
-                known_info.push(result);
-                possible_words = filter_using_known_info(&possible_words, &known_info);
-                possible_words = optimise_results(possible_words, &known_info);
-                if possible_words[0].word == *sw.word {
-                    return 1;
-                }
-                guesses -= 1;
-                if guesses == 0 {
-                    return 0;
-                }
-                guess = possible_words[0].word.clone();
-            }
-        })
-        .sum();
-    (solvable, guess)
-}
+    // looking after the cache doesn't need a word list, or a cache to exist
+    if let Some(Mode::Cache { action }) = &args.mode {
+        return manage_cache(&cache_path, action);
+    }
 
-/// Chooses the optimal strategy for the given word list
-fn choose_optimal_strategy(words: &Vec<ScoredWord>) -> (Strategy, String) {
-    let mut sp = Spinner::new(
-        spinners::Aesthetic,
-        "Choosing optimal strategy for this word list",
-        None,
-    );
-    let mut results: HashMap<Strategy, (i32, String)> = HashMap::new();
+    // create the cache directory if it doesn't exist
+    std::fs::create_dir_all(&cache_dir).map_err(SolverError::io("create", &cache_dir))?;
 
-    let start = std::time::Instant::now();
+    // open read-write, create if it doesn't exist at cache_dir/strategies. it's
+    // read before anything is written, so it mustn't be truncated
+    let mut cache_file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&cache_path)
+        .map_err(SolverError::io("open", &cache_path))?;
 
-    let options = [
-        Strategy::FrequencySimple,
-        Strategy::FrequencyPositionAware,
-        Strategy::Random,
-    ];
+    let mut bytes = vec![];
+
+    cache_file
+        .read_to_end(&mut bytes)
+        .map_err(SolverError::io("read", &cache_path))?;
+
+    // a cache that can't be used only loses the work that was in it
+    let mut cache = read_cache(&bytes).unwrap_or_else(|e| {
+        info!("Rebuilding the cache, as {}", e);
+        WordListCache::default()
+    });
+
+    let mut known_info: Vec<GuessResult> = vec![];
+    let word_list = match &args.word_list {
+        Some(path) => {
+            let contents =
+                std::fs::read_to_string(path).map_err(SolverError::io("read word list", path))?;
+            validate_word_list(&contents).map_err(|reason| {
+                SolverError::InvalidInput(format!("invalid word list: {}", reason))
+            })?;
+            contents
+        }
+        None => WORDS.to_string(),
+    };
+    let mut words = read_word_list(&word_list);
+    let duplicates = normalise_word_list(&mut words);
+    if duplicates > 0 {
+        warn!(
+            "Word list has {} duplicate {}, counting each word once",
+            duplicates,
+            if duplicates == 1 { "entry" } else { "entries" }
+        );
+    }
+    // with a weight column, the likelihoods shown come from the weights rather
+    // than letter frequencies
+    let weights: Option<HashMap<String, usize>> = word_list
+        .lines()
+        .any(|line| line.split_whitespace().nth(1).is_some())
+        .then(|| words.iter().map(|sw| (sw.word.clone(), sw.score)).collect());
+
+    let mut hasher = Hasher::new();
+    hasher.update(&CLUE_RULES_VERSION.to_le_bytes());
+    // hashing the normalised list means reordering the file, or repeating a
+    // word in it, doesn't throw away what's cached for it
+    for sw in &words {
+        hasher.update(format!("{} {}\n", sw.word, sw.score).as_bytes());
+    }
+
+    if let Some(path) = &args.answer_frequencies {
+        let contents = std::fs::read_to_string(path)
+            .map_err(SolverError::io("read answer frequencies", path))?;
+        apply_answer_frequencies(&mut words, &contents);
+        // the weights change the opener, so they need to be part of the cache key
+        hasher.update(contents.as_bytes());
+    }
+
+    if let Some(path) = &args.only {
+        let contents = std::fs::read_to_string(path)
+            .map_err(SolverError::io("read --only word list", path))?;
+        words = intersect_words(words, &contents);
+        if words.is_empty() {
+            eprintln!("{}", "None of the --only words are in the word list".red());
+            std::process::exit(EXIT_NO_MATCHES);
+        }
+        hasher.update(contents.as_bytes());
+    }
+
+    let unscorable: Vec<&str> = words
+        .iter()
+        .filter(|sw| sw.word.chars().any(|c| letter_index(c).is_none()))
+        .map(|sw| sw.word.as_str())
+        .collect();
+    if !unscorable.is_empty() {
+        warn!(
+            "Skipping letters that aren't a-z when scoring {} {}: {}",
+            unscorable.len(),
+            if unscorable.len() == 1 {
+                "word"
+            } else {
+                "words"
+            },
+            unscorable.join(", ")
+        );
+    }
+
+    // probe guesses can be drawn from outside the remaining candidates, and from
+    // any extra guesses given. the answers come first, so they line up with the
+    // pattern matrix's rows
+    let mut all_words = words.clone();
+    if let Some(path) = &args.guesses {
+        let contents =
+            std::fs::read_to_string(path).map_err(SolverError::io("read guess list", path))?;
+        validate_word_list(&contents).map_err(|reason| {
+            SolverError::InvalidInput(format!("invalid guess list: {}", reason))
+        })?;
+        let mut seen: HashSet<String> = words.iter().map(|sw| sw.word.clone()).collect();
+        all_words.extend(
+            read_word_list(&contents)
+                .into_iter()
+                .filter(|sw| seen.insert(sw.word.clone())),
+        );
+        // openers can come from the extra guesses, so they're part of the cache key
+        hasher.update(b"--guesses");
+        hasher.update(contents.as_bytes());
+    }
+
+    if let Some((first, second)) = &args.opener {
+        for opener in [first, second] {
+            if !all_words.iter().any(|sw| sw.word == *opener) {
+                return Err(SolverError::NotInWordList(format!("opener {}", opener)));
+            }
+        }
+    }
+
+    if args.approx_opener {
+        // an approximate opener shouldn't be mistaken for the tested one
+        hasher.update(b"--approx-opener");
+    }
+    if args.hard {
+        // strategies are tested under hard mode rules, so may pick differently
+        hasher.update(b"--hard");
+    }
+
+    // strategies are tested ranking the candidates the way a session does, so
+    // anything that changes the ranking may pick differently too
+    let mut ranking = Ranking {
+        scorer: args.scorer,
+        count_repeats: args.count_repeats,
+        penalise_repeats: args.penalise_repeats,
+        weights: weights.as_ref(),
+        bigrams: args.bigrams.then(|| bigram_frequencies(&words)),
+        tie_seed: args.tie_seed,
+    };
+    if ranking.scorer != Scorer::Frequency {
+        hasher.update(format!("--scorer {:?}", ranking.scorer).as_bytes());
+    }
+    if args.count_repeats {
+        hasher.update(b"--count-repeats");
+    }
+    if args.penalise_repeats {
+        hasher.update(b"--penalise-repeats");
+    }
+    if args.bigrams {
+        hasher.update(b"--bigrams");
+    }
+    if let Some(seed) = args.tie_seed {
+        hasher.update(format!("--tie-seed {}", seed).as_bytes());
+    }
+
+    let words_digest = hasher.finalize();
+
+    // only modes that solve the whole list need the pattern matrix, so it's
+    // loaded the first time one asks for it
+    let patterns_path = format!("{}/patterns", cache_dir);
+    let patterns: OnceCell<Patterns> = OnceCell::new();
+    let load_patterns = || {
+        patterns.get_or_init(|| {
+            Patterns::load(&patterns_path, words_digest.as_bytes(), &words, &all_words)
+        })
+    };
+
+    let strategy_key = StrategyKey {
+        digest: words_digest.as_bytes().to_vec(),
+        strategy: args.strategy,
+        seed: args.seed,
+        max_guesses: args.max_guesses,
+    };
+
+    // only the candidates' frequencies change from round to round, so the whole
+    // list's are counted once and kept
+    let frequencies = match cache.frequencies.get(words_digest.as_bytes().as_slice()) {
+        Some(frequencies) => *frequencies,
+        None => {
+            let frequencies = positional_frequencies(&words);
+            cache
+                .frequencies
+                .insert(words_digest.as_bytes().to_vec(), frequencies);
+            write_cache(&mut cache_file, &cache, &cache_path)?;
+            frequencies
+        }
+    };
+
+    let strategy: Strategy;
+    let first_guess: String;
+
+    if let Some(strat) = cache.strats.get(&strategy_key) {
+        debug!(
+            "Using strategy from cache at {}/strategies for wordset {}",
+            cache_dir,
+            words_digest.to_hex().cyan()
+        );
+        info!(
+            "Cached result from {}: {}{}",
+            format_date(strat.computed_at),
+            format!("{:?}", strat.strategy).magenta(),
+            match strat.stats {
+                Some((solvable, average_guesses)) => format!(
+                    ", solves {}/{} avg {}",
+                    solvable,
+                    words.len(),
+                    average_guesses.smooth_str()
+                ),
+                None => ", untested".to_string(),
+            }
+        );
+        strategy = strat.strategy;
+        first_guess = strat.first_guess.clone();
+    } else {
+        info!(
+            "No cached strategy found, generating one for wordset {}",
+            words_digest.to_hex()
+        );
+        let deadline = args
+            .deadline
+            .map(|secs| Instant::now() + Duration::from_secs_f64(secs));
+        // the strategy gets its own rng, so drawing from it can't shift any other
+        // random choices. without a seed it's seeded from the word list, so the
+        // same list always gets the same strategy and opener
+        let mut strategy_rng = match args.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_seed(*words_digest.as_bytes()),
+        };
+        let (strat, fw, histogram, complete) = if let Some(s) = args.strategy {
+            // asked for by name, so there's no need to test it against the others
+            (
+                s,
+                get_first_guess_using(&words, &all_words, s, &frequencies, &mut strategy_rng),
+                None,
+                true,
+            )
+        } else if args.approx_opener {
+            let opener = anneal_opener(&words, &all_words, deadline, &mut strategy_rng);
+            info!(
+                "Approximate opener is {}, leaving {} words on average",
+                opener.blue(),
+                expected_remaining(&partition(&opener, &words)).smooth_str()
+            );
+            // the search stops at the deadline, so a longer one could find better
+            let complete = !matches!(deadline, Some(deadline) if Instant::now() >= deadline);
+            (Strategy::Annealed, opener, None, complete)
+        } else {
+            choose_optimal_strategy(
+                &words,
+                &all_words,
+                &ranking,
+                args.hard,
+                max_guesses,
+                deadline,
+                &mut strategy_rng,
+                &frequencies,
+                load_patterns(),
+            )
+        };
+
+        // a strategy picked before the deadline cut the search short isn't
+        // the best for this list, so it's only used this once
+        if complete {
+            cache.strats.insert(
+                strategy_key,
+                CachedStrategy {
+                    strategy: strat,
+                    first_guess: fw.clone(),
+                    stats: histogram.map(|h| (h.solved_count(), h.average_guesses())),
+                    computed_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                },
+            );
+            write_cache(&mut cache_file, &cache, &cache_path)?;
+            info!("Cached strategy in {}/strategies", cache_dir);
+        } else {
+            info!("Not caching a strategy chosen before the deadline cut the search short");
+        }
+
+        strategy = strat;
+        first_guess = fw;
+    }
+
+    // a fixed pair of openers takes the place of the strategy's opener wherever
+    // the solver plays from the start
+    let openers: Vec<&str> = match &args.opener {
+        Some((first, second)) => vec![first, second],
+        None => vec![&first_guess],
+    };
+
+    let digest_key = words_digest.as_bytes().to_vec();
+    let transitions_key = TransitionsKey {
+        digest: digest_key.clone(),
+        opener: openers[0].to_string(),
+    };
+    if !cache.transitions.contains_key(&transitions_key) {
+        let transitions = OpenerTransitions::build(&openers[0].to_string(), &words);
+        cache
+            .transitions
+            .insert(transitions_key.clone(), transitions);
+        write_cache(&mut cache_file, &cache, &cache_path)?;
+    }
+
+    match &args.mode {
+        Some(Mode::Solvable { word }) => {
+            return print_solvable(
+                &words,
+                &all_words,
+                &openers,
+                word,
+                &ranking,
+                args.hard,
+                max_guesses,
+                args.md.as_deref(),
+                palette,
+            );
+        }
+        Some(Mode::Solve {
+            answer,
+            explain: true,
+        }) => {
+            return print_explained_solve(
+                &words,
+                &all_words,
+                &openers,
+                &answer.to_lowercase(),
+                &ranking,
+                args.hard,
+                max_guesses,
+                palette,
+            );
+        }
+        Some(Mode::Solve { answer, .. }) => {
+            return print_solve(
+                &words,
+                &all_words,
+                &openers,
+                &answer.to_lowercase(),
+                &ranking,
+                args.hard,
+                max_guesses,
+            );
+        }
+        Some(Mode::Replay { path }) => {
+            let contents =
+                std::fs::read_to_string(path).map_err(SolverError::io("read game", path))?;
+            let game = read_game_log(&contents, &all_words).map_err(|reason| {
+                SolverError::InvalidInput(format!("could not replay {}: {}", path, reason))
+            })?;
+            replay(words, &all_words, &openers, game, &ranking, &args, palette);
+            return Ok(());
+        }
+        Some(Mode::Assist { stdin }) => {
+            let text = if *stdin {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(SolverError::io("read", "stdin"))?;
+                text
+            } else {
+                arboard::Clipboard::new()
+                    .and_then(|mut clipboard| clipboard.get_text())
+                    .map_err(|e| SolverError::Clipboard(e.to_string()))?
+            };
+            let clues = parse_share(&text).map_err(|e| {
+                SolverError::InvalidInput(format!("not a shared Wordle game: {}", e))
+            })?;
+            assist(words, &all_words, &openers, clues, &ranking, &args, palette);
+            return Ok(());
+        }
+        Some(Mode::Analyze {
+            export_stats,
+            sample,
+        }) => {
+            return analyze(
+                &words,
+                &all_words,
+                strategy,
+                &openers,
+                &ranking,
+                export_stats.as_deref(),
+                *sample,
+                &mut seeded_rng(&args),
+                args.hard,
+                max_guesses,
+                // a sample is meant to be quick, so it's not worth precomputing
+                // clues for the whole list
+                sample.is_none().then(load_patterns),
+            );
+        }
+        Some(Mode::CheatSheet { opener, top }) => {
+            print_cheat_sheet(
+                &words,
+                opener.as_ref().unwrap_or(&first_guess),
+                &ranking,
+                *top,
+                palette,
+            );
+            return Ok(());
+        }
+        Some(Mode::Opener) => {
+            return print_opener(strategy, &first_guess, args.json);
+        }
+        Some(Mode::Claim) => {
+            let claim = OpenerClaim::new(
+                &words,
+                &all_words,
+                &words_digest.to_hex(),
+                strategy,
+                &first_guess,
+                &ranking,
+                max_guesses,
+            );
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&claim).map_err(SolverError::serialise("claim"))?
+            );
+            return Ok(());
+        }
+        Some(Mode::VerifyClaim { path }) => {
+            let contents =
+                std::fs::read_to_string(path).map_err(SolverError::io("read claim", path))?;
+            let claim: OpenerClaim = serde_json::from_str(&contents)
+                .map_err(|e| SolverError::InvalidInput(format!("invalid claim: {}", e)))?;
+            claim
+                .verify(
+                    &words,
+                    &all_words,
+                    &frequencies,
+                    &words_digest.to_hex(),
+                    &ranking,
+                    max_guesses,
+                )
+                .map_err(|reason| {
+                    SolverError::InvalidInput(format!("claim does not hold: {}", reason))
+                })?;
+            println!("{}", "Claim verified".green());
+            return Ok(());
+        }
+        Some(Mode::EntropyProfile) => {
+            return print_entropy_profile(&words, args.json);
+        }
+        Some(Mode::GenFixtures { seed, count }) => {
+            let fixtures = gen_fixtures(&words, &first_guess, &ranking, *seed, *count, max_guesses);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&fixtures)
+                    .map_err(SolverError::serialise("fixtures"))?
+            );
+            return Ok(());
+        }
+        Some(Mode::Bench {
+            strategy: bench_strategy,
+        }) => {
+            let (strategy, opener) = match bench_strategy {
+                // the benchmark gets its own rng, like the other random draws
+                Some(s) => (
+                    *s,
+                    get_first_guess_using(
+                        &words,
+                        &all_words,
+                        *s,
+                        &frequencies,
+                        &mut seeded_rng(&args),
+                    ),
+                ),
+                None => (strategy, first_guess.clone()),
+            };
+            // a fixed pair is benchmarked as given, whichever strategy is named
+            let openers = match &args.opener {
+                Some(_) => openers,
+                None => vec![opener.as_str()],
+            };
+            let report = bench(
+                &words,
+                &all_words,
+                strategy,
+                &openers,
+                &ranking,
+                args.hard,
+                max_guesses,
+                load_patterns(),
+            );
+            return print_bench(&report, args.json);
+        }
+        Some(Mode::SolveCdf { opener }) => {
+            let opener = opener.as_ref().unwrap_or(&first_guess);
+            let histogram = GuessHistogram::from_traces(
+                &evaluate_first_guess(
+                    &words,
+                    &all_words,
+                    opener,
+                    &ranking,
+                    args.hard,
+                    max_guesses,
+                    Some(load_patterns()),
+                ),
+                max_guesses,
+            );
+            return print_solve_cdf(opener, &histogram, args.json);
+        }
+        Some(Mode::Coverage { min }) => {
+            return print_coverage_gaps(&coverage_gaps(&words, *min), args.json);
+        }
+        Some(Mode::EvalOpeners { path }) => {
+            let contents =
+                std::fs::read_to_string(path).map_err(SolverError::io("read openers", path))?;
+            let evaluations = eval_openers(
+                &words,
+                &all_words,
+                &contents,
+                &ranking,
+                args.hard,
+                max_guesses,
+                load_patterns(),
+            );
+            return print_opener_evaluations(&evaluations, args.json);
+        }
+        Some(Mode::BestPair { top }) => {
+            let (first, second) = match cache.pairs.get(&digest_key) {
+                Some((searched, first, second)) if searched == top => {
+                    (first.clone(), second.clone())
+                }
+                _ => {
+                    let pair = best_pair(&words, *top).ok_or(SolverError::NoPair)?;
+                    cache
+                        .pairs
+                        .insert(digest_key.clone(), (*top, pair.0.clone(), pair.1.clone()));
+                    write_cache(&mut cache_file, &cache, &cache_path)?;
+                    pair
+                }
+            };
+            print_pair(&words, &first, &second);
+            return Ok(());
+        }
+        // handled before anything was loaded
+        Some(Mode::Cache { .. }) | None => {}
+    }
+    if args.batch {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(SolverError::io("read", "stdin"))?;
+        let game = if text.trim().is_empty() {
+            vec![]
+        } else {
+            read_game_log(&text, &all_words).map_err(|reason| {
+                SolverError::InvalidInput(format!("could not read the clues: {}", reason))
+            })?
+        };
+        match batch_guess(words, &all_words, &openers, game, &ranking, args.hard) {
+            Ok(guess) => println!("{}", guess),
+            Err(round) => {
+                eprintln!(
+                    "{}",
+                    format!("No words match the clues up to guess {}", round).red()
+                );
+                std::process::exit(EXIT_NO_MATCHES);
+            }
+        }
+        return Ok(());
+    }
+    if args.json {
+        return json_session(words, &all_words, &openers, &ranking, &args);
+    }
+    if args.tui {
+        if !std::io::stdout().is_terminal() {
+            return Err(SolverError::NoTerminal);
+        }
+        return tui::run(words, &all_words, &openers, ranking, args.hard, palette)
+            .map_err(SolverError::Tui);
+    }
+    let transitions = &cache.transitions[&transitions_key];
+
+    let mut last_guess = openers[0].to_string();
+    let mut transcript = args
+        .md
+        .as_deref()
+        .map(|path| MarkdownTranscript::new(path, palette));
+    let mut teacher = (args.teach || args.teach_file.is_some())
+        .then(|| Teacher::new(args.teach_file.as_deref()))
+        .transpose()?;
+
+    // the candidates and guess from before each clue, so `undo` can go back to
+    // them exactly, tie shuffles and all
+    let mut history: Vec<(Vec<ScoredWord>, String)> = vec![];
+
+    println!("\n{}", "TIPS:".bold());
+    println!(" - Leave a field empty to autofill all empty letters with that colour");
+    println!(" - If you type less than 5 letters, we'll replace the rest with dashes");
+    println!(" - Use '*' for a wildcard tile that could be any letter");
+    println!(" - Type 'try <word>' to see how a word would split the remaining words");
+    println!(" - Type 'show' to list every remaining word");
+    println!(" - Type 'giveup' to see the most likely answers and stop");
+    println!(" - Type 'undo' to take back the last result you entered");
+    println!(
+        " - Type 'assume <word> <pattern>', e.g. 'assume crane bgybb', to see what a clue would leave"
+    );
+    println!(" - Type 'strategy <frequency|entropy|minimax>' to change how words are ranked");
+
+    // for our first guess, we have no information, so we just guess the word
+    // not as an actual word, but as the top 5 letters in the word list by frequency
+    println!("\nFirst guess is {}!", openers[0].blue());
+    if let Some(teacher) = &mut teacher {
+        teacher.note(match &args.opener {
+            Some((first, second)) => format!(
+                "Step 1: {} words to start with, opening with {} then {} whatever the clues",
+                words.len(),
+                first,
+                second
+            ),
+            None => format!(
+                "Step 1: {} words to start with, opening with {}, picked by the {:?} strategy",
+                words.len(),
+                first_guess,
+                strategy
+            ),
+        })?;
+    }
+
+    // each round the clue for the latest suggestion is read, then the next is
+    // suggested, so there's never a clue asked for a word that wasn't suggested
+    let mut round = 1;
+    'rounds: loop {
+        println!(
+            "\n{}",
+            format!("Guess {} of {}", round, max_guesses).black()
+        );
+        // we get input from the user which we can use to refine our guess. a
+        // mistyped colour can rule out every word, so the result can be
+        // entered again until some are left
+        let (filtered_results, start, elapsed) = loop {
+            let Some(guess_result) =
+                get_guess_result(&last_guess, &words, &args, &mut ranking.scorer, palette)
+            else {
+                let Some((previous_words, previous_guess)) = history.pop() else {
+                    println!("Nothing to undo yet");
+                    continue;
+                };
+                known_info.pop();
+                if let Some(transcript) = &mut transcript {
+                    transcript.undo()?;
+                }
+                println!("Undid the result for {}", previous_guess.blue());
+                words = previous_words;
+                last_guess = previous_guess;
+                round -= 1;
+                continue 'rounds;
+            };
+            if args.explain_filter {
+                let explanation = explain_filter(&words, &guess_result)
+                    .iter()
+                    .map(|(rule, removed)| format!("{} removed {}", rule, removed))
+                    .collect::<Vec<String>>()
+                    .join("; ");
+                println!("{} {}", "[explain]".black(), explanation);
+            }
+            known_info.push(guess_result);
+            let start = std::time::Instant::now();
+            // the opener's clue can be looked up rather than filtered for. the
+            // candidates already fit every earlier clue, so only the newest one
+            // is left to filter by
+            let filtered_results = match transitions.lookup(&known_info, &all_words) {
+                Some(candidates) => candidates,
+                None => filter_using_known_info(&words, newest_clue(&known_info)),
+            };
+            let elapsed = start.elapsed();
+            if !filtered_results.is_empty() {
+                break (filtered_results, start, elapsed);
+            }
+
+            known_info.pop();
+            println!(
+                "{}",
+                "No words match these clues — did you mistype a colour?".red()
+            );
+            print!("Re-enter the result for {}? (y): ", last_guess.blue());
+            if !matches!(read_stdin_line().as_str(), "y" | "") {
+                std::process::exit(EXIT_NO_MATCHES);
+            }
+        };
+        history.push((words.clone(), last_guess.clone()));
+        if let Some(teacher) = &mut teacher {
+            teacher.note(format!(
+                "Step {}: you reported {} for {}",
+                round,
+                known_info.last().unwrap().pattern(),
+                last_guess
+            ))?;
+        }
+        // a single word left is the answer, so there's nothing to rank or ask
+        // about. no words left never gets here, the clue is asked for again
+        if let [answer] = &filtered_results[..] {
+            println!("\nThe word is {}!", answer.word.blue());
+            if let Some(transcript) = &mut transcript {
+                transcript.record(
+                    &last_guess,
+                    known_info.last().unwrap(),
+                    1,
+                    format!("only {} fits every clue", answer.word),
+                )?;
+            }
+            break;
+        }
+        let total_chars = filtered_results.iter().map(|s| s.word.len()).sum::<usize>();
+
+        debug!(
+            "Filtered in {:?} ({} char/s)",
+            elapsed,
+            (total_chars as f64 / start.elapsed().as_secs_f64()).smooth_str()
+        );
+        println!(
+            "\nFound {} possible {}",
+            filtered_results.len(),
+            if filtered_results.len() == 1 {
+                "word"
+            } else {
+                "words"
+            },
+        );
+        let start = std::time::Instant::now();
+        words = ranking.rank(filtered_results, &known_info);
+        let elapsed = start.elapsed();
+        debug!(
+            "Scored & reordered results in {:?} ({} char/s)",
+            elapsed,
+            (total_chars as f64 / start.elapsed().as_secs_f64()).smooth_str()
+        );
+
+        let Some(top) = words.first() else {
+            // ranking never drops words, but there's nothing to suggest if it did
+            eprintln!("{}", "No words match these clues".red());
+            std::process::exit(EXIT_NO_MATCHES);
+        };
+        let guess = std::mem::replace(&mut last_guess, top.word.clone());
+        let mut reasoning = match ranking.scorer {
+            Scorer::Frequency => describe_choice(&words, &known_info, args.count_repeats),
+            Scorer::Entropy => format!(
+                "suggested {}, which leaves the fewest words on average",
+                last_guess
+            ),
+            Scorer::Minimax => format!(
+                "suggested {}, which leaves the fewest words in the worst case",
+                last_guess
+            ),
+        };
+        if let Some(teacher) = &mut teacher {
+            // entropy and minimax rankings don't follow the score, so there's
+            // nothing to break down
+            if ranking.scorer == Scorer::Frequency {
+                teacher.explain_choice(round + 1, &words, &known_info, args.count_repeats)?;
+            }
+        }
+
+        let total_score = words.par_iter().map(|sw| sw.score).sum::<usize>();
+
+        // the next guess is the last, so every candidate is worth seeing
+        if words.len() < 5 || round + 1 == max_guesses {
+            let cells = words
+                .iter()
+                .map(|sw| {
+                    (
+                        sw.word.clone(),
+                        format!(
+                            " ({})",
+                            describe_suggestion(sw, &words, total_score, args.expected_remaining)
+                        ),
+                    )
+                })
+                .collect::<Vec<(String, String)>>();
+            println!(
+                "Try one of these: \n{}",
+                format_columns(&cells, terminal_width(&args))
+            );
+        } else if let Some(sw) = words.first() {
+            println!(
+                "Try {} ({})",
+                sw.word.blue(),
+                describe_suggestion(sw, &words, total_score, args.expected_remaining)
+            );
+        }
+
+        // frequency scoring can't tell anagrams apart, so if they make up most of
+        // what's left we suggest a guess that separates them by position instead.
+        // the rest of a fixed opening is played whatever the clues, unless
+        // they've already found the answer
+        match choose_next_guess(&words, &all_words, &openers, round, args.hard) {
+            Some(NextGuess::Probe { cluster, probe }) => {
+                println!(
+                    "{} of the remaining words are anagrams ({}), try {} to tell them apart",
+                    cluster.len(),
+                    cluster.join(", "),
+                    probe.blue()
+                );
+                if let Some(teacher) = &mut teacher {
+                    teacher.note(format!(
+                        "Step {}: anagrams can't be told apart by score, so {} was suggested instead",
+                        round + 1,
+                        probe
+                    ))?;
+                }
+                reasoning = format!(
+                    "anagrams can't be told apart by score, so suggested {} instead",
+                    probe
+                );
+                last_guess = probe;
+            }
+            Some(NextGuess::Opener(opener)) => {
+                println!("Fixed opening: play {} next instead", opener.blue());
+                reasoning = format!("played {} as part of the fixed opening", opener);
+                last_guess = opener;
+            }
+            Some(NextGuess::Likeliest(_)) | None => {}
+        }
+        if let Some(transcript) = &mut transcript {
+            transcript.record(&guess, known_info.last().unwrap(), words.len(), reasoning)?;
+        }
+
+        if let Some((letter, count)) = most_informative_letter(&words, &known_info) {
+            println!(
+                "{} '{}' splits the remaining words most evenly ({}/{} contain it)",
+                "Hint:".black(),
+                letter.to_string().magenta(),
+                count,
+                words.len()
+            );
+        }
+
+        round += 1;
+        if round == max_guesses {
+            println!(
+                "\n{}",
+                format!(
+                    "Guess {} of {} is the last one, good luck!",
+                    round, max_guesses
+                )
+                .black()
+            );
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Runs a session for another program to drive over stdin and stdout. Each
+/// round a `RoundMessage` is written as a line of JSON, and a `ClueMessage` is
+/// read back the same way. A clue that can't be read or rules out every word
+/// gets an `ErrorMessage`, and another is waited for. Ends when stdin closes.
+fn json_session(
+    mut words: Vec<ScoredWord>,
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    ranking: &Ranking,
+    args: &Args,
+) -> Result<(), SolverError> {
+    let mut known_info: Vec<GuessResult> = vec![];
+    let mut guess = openers[0].to_string();
+    let mut lines = std::io::stdin().lines();
+    words = ranking.rank(words, &known_info);
+    loop {
+        send_json(&RoundMessage::new(&words, &guess, JSON_SUGGESTIONS))?;
+        let filtered_results = loop {
+            let Some(Ok(line)) = lines.next() else {
+                return Ok(());
+            };
+            let clue = match serde_json::from_str::<ClueMessage>(&line) {
+                Ok(clue) => clue,
+                Err(e) => {
+                    send_json(&ErrorMessage {
+                        error: format!("Couldn't read that clue: {}", e),
+                    })?;
+                    continue;
+                }
+            };
+            match parse_pattern(&clue.guess.to_lowercase(), &clue.pattern) {
+                Ok(result) => known_info.push(result),
+                Err(e) => {
+                    send_json(&ErrorMessage {
+                        error: format!("Couldn't read that result: {}", e),
+                    })?;
+                    continue;
+                }
+            }
+            let filtered_results = filter_using_known_info(&words, newest_clue(&known_info));
+            if !filtered_results.is_empty() {
+                break filtered_results;
+            }
+            known_info.pop();
+            send_json(&ErrorMessage {
+                error: "No words match these clues".to_string(),
+            })?;
+        };
+
+        // suggestions are picked the same way as in a session
+        words = ranking.rank(filtered_results, &known_info);
+        guess = next_guess(&words, all_words, openers, known_info.len(), args.hard);
+    }
+}
+
+/// Prints each clue of a game that's already been played next to what the
+/// solver would have played that round, given the same clues before it, and
+/// how many words each clue left
+fn assist(
+    words: Vec<ScoredWord>,
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    clues: Vec<GuessResult>,
+    ranking: &Ranking,
+    args: &Args,
+    palette: Palette,
+) {
+    let mut known_info: Vec<GuessResult> = vec![];
+    let mut words = ranking.rank(words, &known_info);
+    for (round, clue) in clues.into_iter().enumerate() {
+        let suggestion = next_guess(&words, all_words, openers, round, args.hard);
+        println!(
+            "  {} {} the solver would have played {}",
+            format!("{}.", round + 1).black(),
+            clue.paint(palette),
+            suggestion.blue()
+        );
+        known_info.push(clue);
+        let filtered_results = filter_using_known_info(&words, newest_clue(&known_info));
+        if filtered_results.is_empty() {
+            eprintln!(
+                "{}",
+                "No words match these clues, so the solver can't follow the rest".red()
+            );
+            std::process::exit(EXIT_NO_MATCHES);
+        }
+        words = ranking.rank(filtered_results, &known_info);
+        println!(
+            "     {} {} left",
+            words.len(),
+            if words.len() == 1 { "word" } else { "words" }
+        );
+    }
+}
+
+/// Reads a logged game for `replay`, one guess and its result per line, like
+/// `crane gybrr`. Blank lines are skipped, and every guess has to be one the
+/// solver knows. Errors say which line is wrong
+fn read_game_log(
+    contents: &str,
+    all_words: &[ScoredWord],
+) -> Result<Vec<(String, GuessResult)>, String> {
+    let mut game = vec![];
+    for (n, line) in contents.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (guess, pattern) = match fields[..] {
+            [] => continue,
+            [guess, pattern] => (guess.to_lowercase(), pattern),
+            _ => {
+                return Err(format!(
+                    "line {} should be a guess and its result, like `crane gybrr`",
+                    n + 1
+                ))
+            }
+        };
+        if !all_words.iter().any(|sw| sw.word == guess) {
+            return Err(format!("line {}: {} is not in the word list", n + 1, guess));
+        }
+        let clue = parse_pattern(&guess, pattern).map_err(|e| format!("line {}: {}", n + 1, e))?;
+        game.push((guess, clue));
+    }
+    if game.is_empty() {
+        return Err("there are no guesses in it".to_string());
+    }
+    Ok(game)
+}
+
+/// Follows the clues of a game played so far and returns the guess the solver
+/// would play next, or the number of the first guess whose clue left no words
+fn batch_guess(
+    words: Vec<ScoredWord>,
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    game: Vec<(String, GuessResult)>,
+    ranking: &Ranking,
+    hard: bool,
+) -> Result<String, usize> {
+    let mut known_info: Vec<GuessResult> = vec![];
+    let mut words = ranking.rank(words, &known_info);
+    for (round, (_, clue)) in game.into_iter().enumerate() {
+        known_info.push(clue);
+        let filtered_results = filter_using_known_info(&words, newest_clue(&known_info));
+        if filtered_results.is_empty() {
+            return Err(round + 1);
+        }
+        words = ranking.rank(filtered_results, &known_info);
+    }
+    Ok(next_guess(
+        &words,
+        all_words,
+        openers,
+        known_info.len(),
+        hard,
+    ))
+}
+
+/// Plays through a logged game, printing the words left and the solver's top
+/// suggestion after each guess, then whether the solver would have made the
+/// same last guess
+fn replay(
+    words: Vec<ScoredWord>,
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    game: Vec<(String, GuessResult)>,
+    ranking: &Ranking,
+    args: &Args,
+    palette: Palette,
+) {
+    let rounds = game.len();
+    let mut known_info: Vec<GuessResult> = vec![];
+    let mut words = ranking.rank(words, &known_info);
+    let mut suggestion = next_guess(&words, all_words, openers, 0, args.hard);
+    let mut last = None;
+    for (round, (guess, clue)) in game.into_iter().enumerate() {
+        if round + 1 == rounds {
+            last = Some((guess, suggestion.clone()));
+        }
+        known_info.push(clue);
+        let filtered_results = filter_using_known_info(&words, newest_clue(&known_info));
+        if filtered_results.is_empty() {
+            eprintln!(
+                "{}",
+                format!(
+                    "No words match the clues up to guess {}, so the solver can't follow the rest",
+                    round + 1
+                )
+                .red()
+            );
+            std::process::exit(EXIT_NO_MATCHES);
+        }
+        words = ranking.rank(filtered_results, &known_info);
+        suggestion = next_guess(&words, all_words, openers, round + 1, args.hard);
+        println!(
+            "  {} {} leaves {} {}, the solver would play {}",
+            format!("{}.", round + 1).black(),
+            known_info.last().unwrap().paint(palette),
+            words.len(),
+            if words.len() == 1 { "word" } else { "words" },
+            suggestion.blue()
+        );
+    }
+
+    let (guess, pick) = last.expect("a game log has at least one guess");
+    if guess == pick {
+        println!("\nThe solver would have played {} last too", guess.blue());
+    } else {
+        println!(
+            "\nThe solver would have played {} last instead of {}",
+            pick.blue(),
+            guess.blue()
+        );
+    }
+}
+
+/// Writes a message as a single line of JSON
+fn send_json(message: &impl serde::Serialize) -> Result<(), SolverError> {
+    println!(
+        "{}",
+        serde_json::to_string(message).map_err(SolverError::serialise("message"))?
+    );
+    Ok(())
+}
+
+/// Reads the cache written by `write_cache`. An empty file is an empty cache,
+/// while one written with another schema version, or that can't be read, is an
+/// error that's safe to recover from by starting again
+fn read_cache(bytes: &[u8]) -> Result<WordListCache, SolverError> {
+    let Some((version, rest)) = bytes.split_first() else {
+        return Ok(WordListCache::default());
+    };
+    if *version != CACHE_SCHEMA_VERSION {
+        return Err(SolverError::OutdatedCache);
+    }
+    // the archive has to be aligned, which it won't be after the version byte
+    let mut aligned = AlignedVec::new();
+    aligned.extend_from_slice(rest);
+    rkyv::from_bytes(&aligned).map_err(|_| SolverError::CorruptCache)
+}
+
+/// Overwrites the cache file at `path` with the given cache, after the schema
+/// version
+fn write_cache(
+    cache_file: &mut std::fs::File,
+    cache: &WordListCache,
+    path: &str,
+) -> Result<(), SolverError> {
+    let bytes =
+        rkyv::to_bytes::<WordListCache, 4096>(cache).map_err(SolverError::serialise("cache"))?;
+    cache_file
+        .set_len(0)
+        .and_then(|_| cache_file.rewind())
+        .and_then(|_| cache_file.write_all(&[CACHE_SCHEMA_VERSION]))
+        .and_then(|_| cache_file.write_all(&bytes))
+        .map_err(SolverError::io("write", path))
+}
+
+/// A wordset's digest, which the caches are keyed by, in hex
+fn cache_key_digest(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lists or clears the cache at `path`, reporting that there's no cache if the
+/// file is missing or can't be read
+fn manage_cache(path: &str, action: &CacheAction) -> Result<(), SolverError> {
+    let Some(mut cache) = std::fs::read(path)
+        .ok()
+        .and_then(|bytes| read_cache(&bytes).ok())
+    else {
+        println!("No cache");
+        return Ok(());
+    };
+    match action {
+        CacheAction::List => {
+            if cache.strats.is_empty() {
+                println!("No cache");
+                return Ok(());
+            }
+            let mut strats: Vec<(&StrategyKey, &CachedStrategy)> = cache.strats.iter().collect();
+            strats.sort_by_key(|(_, strat)| strat.computed_at);
+            for (key, strat) in strats {
+                println!(
+                    "{}{} {} opens with {}{}, chosen {}",
+                    cache_key_digest(&key.digest).cyan(),
+                    key.flags().black(),
+                    format!("{:?}", strat.strategy).magenta(),
+                    strat.first_guess.blue(),
+                    match strat.stats {
+                        Some((solvable, average_guesses)) => format!(
+                            ", solves {} averaging {}",
+                            solvable,
+                            average_guesses.smooth_str()
+                        ),
+                        None => String::new(),
+                    },
+                    format_date(strat.computed_at)
+                );
+            }
+        }
+        CacheAction::Clear { digest: None } => {
+            std::fs::remove_file(path).map_err(SolverError::io("remove", path))?;
+            println!("Cleared the cache");
+        }
+        CacheAction::Clear {
+            digest: Some(prefix),
+        } => {
+            let prefix = prefix.to_lowercase();
+            let matches = |digest: &Vec<u8>| cache_key_digest(digest).starts_with(&prefix);
+            let strats = cache.strats.len();
+            cache.strats.retain(|key, _| !matches(&key.digest));
+            let removed = strats - cache.strats.len();
+            let before = cache.transitions.len() + cache.pairs.len() + cache.frequencies.len();
+            cache.transitions.retain(|key, _| !matches(&key.digest));
+            cache.pairs.retain(|key, _| !matches(key));
+            cache.frequencies.retain(|key, _| !matches(key));
+            let after = cache.transitions.len() + cache.pairs.len() + cache.frequencies.len();
+            if removed == 0 && before == after {
+                return Err(SolverError::NothingCached(prefix.clone()));
+            }
+            let mut cache_file = std::fs::OpenOptions::new()
+                .write(true)
+                .open(path)
+                .map_err(SolverError::io("open", path))?;
+            write_cache(&mut cache_file, &cache, path)?;
+            println!(
+                "Removed {} cached {} for {}",
+                removed,
+                if removed == 1 {
+                    "strategy"
+                } else {
+                    "strategies"
+                },
+                prefix
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The UTC date of a Unix timestamp as YYYY-MM-DD
+fn format_date(unix_secs: u64) -> String {
+    // days since 1970-01-01 to a civil date, counting years from March so the
+    // leap day falls at the end
+    let days = (unix_secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Breaks down the effect of a guess result on a wordlist by applying each tile
+/// as its own rule, one after another, then the letter counts it implies, and
+/// counting how many words each one eliminated. The counts add up to the total
+/// removed by the whole result.
+fn explain_filter(words: &[ScoredWord], guess: &GuessResult) -> Vec<(String, usize)> {
+    let mut remaining: Vec<&str> = words.iter().map(|sw| sw.word.as_str()).collect();
+    let mut explanation: Vec<(String, usize)> = guess
+        .0
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let before = remaining.len();
+            remaining.retain(|word| matches_tile(word, guess, i, c));
+            let rule = match c {
+                Character::Yellow(t) => format!("yellow '{}'@{}", t, i + 1),
+                Character::Green(t) => format!("green '{}'@{}", t, i + 1),
+                Character::Red(t) => format!("red '{}'", t),
+                Character::Wild => format!("wild @{}", i + 1),
+                Character::Empty => unreachable!("Empty character in guess result"),
+            };
+            (rule, before - remaining.len())
+        })
+        .collect();
+
+    let mut bounds: Vec<(char, (usize, Option<usize>))> =
+        letter_bounds(guess).into_iter().collect();
+    bounds.sort_unstable_by_key(|(t, _)| *t);
+    for (t, (min, max)) in bounds {
+        // a single copy with no upper limit is already covered by the tiles
+        if min < 2 && max.is_none() {
+            continue;
+        }
+        let before = remaining.len();
+        remaining.retain(|word| matches_count(word, t, (min, max)));
+        let rule = match max {
+            Some(max) if max == min => format!("exactly {} '{}'", min, t),
+            _ => format!("at least {} '{}'", min, t),
+        };
+        explanation.push((rule, before - remaining.len()));
+    }
+    explanation
+}
+
+/// Breaks a word's score from `optimise_results` down into what each scored
+/// position added: the letter there and how many candidates share it. Unless
+/// `count_repeats` is set, a letter repeated in a later scored position adds
+/// nothing, so it's left out.
+fn score_components(
+    word: &str,
+    candidates: &[ScoredWord],
+    known_info: &[GuessResult],
+    count_repeats: bool,
+) -> Vec<(usize, char, usize)> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut seen = HashSet::new();
+    scored_positions(known_info)
+        .into_iter()
+        .filter(|i| seen.insert(chars[*i]) || count_repeats)
+        .map(|i| {
+            let count = candidates
+                .iter()
+                .filter(|sw| sw.word.chars().nth(i) == Some(chars[i]))
+                .count();
+            (i, chars[i], count)
+        })
+        .collect()
+}
+
+/// Narrates the solver's choices for `--teach`, echoing each step to the
+/// terminal and to the transcript file if there is one
+struct Teacher {
+    /// The transcript file and its path, for saying which file couldn't be
+    /// written
+    file: Option<(std::fs::File, String)>,
+}
+
+impl Teacher {
+    fn new(path: Option<&str>) -> Result<Self, SolverError> {
+        let file = match path {
+            Some(path) => Some((
+                std::fs::File::create(path).map_err(SolverError::io("create", path))?,
+                path.to_string(),
+            )),
+            None => None,
+        };
+        Ok(Teacher { file })
+    }
+
+    fn note(&mut self, line: String) -> Result<(), SolverError> {
+        println!("{} {}", "[teach]".black(), line);
+        if let Some((file, path)) = &mut self.file {
+            writeln!(file, "{}", line).map_err(SolverError::io("write to", path))?;
+        }
+        Ok(())
+    }
+
+    /// Explains why the top candidate was suggested
+    fn explain_choice(
+        &mut self,
+        step: usize,
+        words: &[ScoredWord],
+        known_info: &[GuessResult],
+        count_repeats: bool,
+    ) -> Result<(), SolverError> {
+        self.note(format!(
+            "Step {}: {} {} left",
+            step,
+            words.len(),
+            if words.len() == 1 { "word" } else { "words" }
+        ))?;
+        self.note(format!(
+            "Step {}: {}",
+            step,
+            describe_choice(words, known_info, count_repeats)
+        ))
+    }
+}
+
+/// Explains why the top candidate comes first, from what its score is made of
+fn describe_choice(
+    words: &[ScoredWord],
+    known_info: &[GuessResult],
+    count_repeats: bool,
+) -> String {
+    let Some(sw) = words.first() else {
+        return "no words are left to choose from".to_string();
+    };
+    let components = score_components(&sw.word, words, known_info, count_repeats);
+    if components.is_empty() {
+        return format!(
+            "no position has only had yellow clues, so every word scores the same and {} is first alphabetically",
+            sw.word
+        );
+    }
+    let parts: Vec<String> = components
+        .iter()
+        .map(|(i, c, count)| format!("'{}'@{} in {} words", c, i + 1, count))
+        .collect();
+    format!(
+        "suggested {}, scoring {} (1 + {})",
+        sw.word,
+        sw.score,
+        parts.join(" + ")
+    )
+}
+
+/// A solve written up as Markdown for `--md`. The file is rewritten after every
+/// guess, so it's complete however the session ends.
+struct MarkdownTranscript {
+    path: String,
+    /// The colours the clues' squares are drawn in
+    palette: Palette,
+    /// Each guess, its clue as emoji, how many words were left after it, and why
+    /// the next guess was chosen
+    steps: Vec<(String, String, usize, String)>,
+}
+
+impl MarkdownTranscript {
+    fn new(path: &str, palette: Palette) -> Self {
+        MarkdownTranscript {
+            path: path.to_string(),
+            palette,
+            steps: vec![],
+        }
+    }
+
+    fn record(
+        &mut self,
+        guess: &str,
+        clue: &GuessResult,
+        remaining: usize,
+        reasoning: String,
+    ) -> Result<(), SolverError> {
+        // the reasoning reads as the end of a sentence elsewhere, but starts one here
+        let mut chars = reasoning.chars();
+        let reasoning = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default();
+        self.steps.push((
+            guess.to_string(),
+            clue.emoji(self.palette),
+            remaining,
+            reasoning,
+        ));
+        self.write()
+    }
+
+    fn write(&self) -> Result<(), SolverError> {
+        let mut md = String::from("# Wordle solve\n\n");
+        for (_, emoji, _, _) in &self.steps {
+            md.push_str(&format!("{}  \n", emoji));
+        }
+        for (i, (guess, emoji, remaining, reasoning)) in self.steps.iter().enumerate() {
+            md.push_str(&format!(
+                "\n## Guess {}: `{}`\n\n{}\n\n{} {} left. {}\n",
+                i + 1,
+                guess,
+                emoji,
+                remaining,
+                if *remaining == 1 { "word" } else { "words" },
+                reasoning
+            ));
+        }
+        std::fs::write(&self.path, md).map_err(SolverError::io("write", &self.path))
+    }
+
+    /// Drops the latest step, for when its clue is taken back
+    fn undo(&mut self) -> Result<(), SolverError> {
+        self.steps.pop();
+        self.write()
+    }
+}
+
+/// Returns the width candidate lists should fit in, preferring the `--width`
+/// override, then the `COLUMNS` environment variable.
+fn terminal_width(args: &Args) -> usize {
+    args.width
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Lays cells out top-to-bottom then left-to-right in as many equal columns as
+/// fit within `width`, the same way `ls` does. Returns the rows of cell indices.
+fn column_layout(cell_widths: &[usize], width: usize) -> Vec<Vec<usize>> {
+    if cell_widths.is_empty() {
+        return vec![];
+    }
+    let column_width = cell_widths.iter().max().unwrap() + COLUMN_GAP;
+    // always use at least one column, even if a cell is wider than the terminal
+    let columns = (width / column_width).clamp(1, cell_widths.len());
+    let rows = cell_widths.len().div_ceil(columns);
+
+    (0..rows)
+        .map(|row| {
+            (0..columns)
+                .map(|column| column * rows + row)
+                .filter(|i| *i < cell_widths.len())
+                .collect()
+        })
+        .collect()
+}
+
+/// What `try <word>` reports: how many groups the word would split the
+/// candidates into, the biggest group, and how many words it leaves on average
+fn describe_try(word: &String, words: &[ScoredWord]) -> String {
+    let buckets = partition(word, words);
+    let worst = buckets.values().max().copied().unwrap_or(0);
+    format!(
+        "{} would split {} {} into {} {}, leaving at most {} and {} on average",
+        word.blue(),
+        words.len(),
+        if words.len() == 1 { "word" } else { "words" },
+        buckets.len(),
+        if buckets.len() == 1 {
+            "group"
+        } else {
+            "groups"
+        },
+        worst,
+        expected_remaining(&buckets).smooth_str()
+    )
+}
+
+/// What `assume <word> <pattern>` reports: how many candidates would be left
+/// if `word` got that clue, and the first few of them
+fn describe_assumption(
+    word: &str,
+    pattern: &str,
+    words: &[ScoredWord],
+    colors: ClueLetters,
+) -> String {
+    let clue = match parse_pattern_with(word, pattern, colors) {
+        Ok(clue) => clue,
+        Err(e) => return format!("{}, e.g. assume crane bgybb", e),
+    };
+    // the candidates already satisfy the known info, so filtering them by the
+    // assumed clue alone is the same as adding it to the known info
+    let remaining = filter_using_known_info(words, &[clue]);
+    let top: Vec<String> = remaining
+        .iter()
+        .take(5)
+        .map(|sw| sw.word.blue().to_string())
+        .collect();
+    format!(
+        "If {} got {}, {} {} would be left{}",
+        word.blue(),
+        pattern,
+        remaining.len(),
+        if remaining.len() == 1 {
+            "word"
+        } else {
+            "words"
+        },
+        if top.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", top.join(", "))
+        }
+    )
+}
+
+/// How likely a suggestion is to be the answer and how much its clue is
+/// expected to tell, like `12.5%, 4.2 bits`. With `show_remaining`, also how
+/// many of `words` it leaves on average, worked out from the same partition.
+fn describe_suggestion(
+    sw: &ScoredWord,
+    words: &[ScoredWord],
+    total_score: usize,
+    show_remaining: bool,
+) -> String {
+    let buckets = partition(&sw.word, words);
+    let mut description = format!(
+        "{}%, {} bits",
+        (100.0 * (sw.score as f64) / (total_score as f64)).smooth_str(),
+        partition_entropy(&buckets).smooth_str()
+    );
+    if show_remaining {
+        description.push_str(&format!(
+            ", leaves ~{} words",
+            expected_remaining(&buckets).smooth_str()
+        ));
+    }
+    description
+}
+
+/// Formats `(word, detail)` cells into aligned columns fitted to `width`, with
+/// the words highlighted.
+fn format_columns(cells: &[(String, String)], width: usize) -> String {
+    let cell_widths: Vec<usize> = cells
+        .iter()
+        .map(|(word, detail)| word.chars().count() + detail.chars().count())
+        .collect();
+    let column_width = cell_widths.iter().max().copied().unwrap_or(0) + COLUMN_GAP;
+
+    // leave room for the indent at the start of each line
+    column_layout(&cell_widths, width.saturating_sub(COLUMN_GAP))
+        .iter()
+        .map(|row| {
+            let mut line = " ".repeat(COLUMN_GAP);
+            for (n, i) in row.iter().enumerate() {
+                let (word, detail) = &cells[*i];
+                line.push_str(&format!("{}{}", word.blue(), detail));
+                // pad manually, as the colour codes would throw off format!'s width
+                if n != row.len() - 1 {
+                    line.push_str(&" ".repeat(column_width - cell_widths[*i]));
+                }
+            }
+            line
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Finds the letter not yet seen in any clue that comes closest to splitting
+/// the candidates in half between the words containing it and the rest,
+/// returning it with how many words contain it. Returns `None` if no untried
+/// letter splits them at all.
+fn most_informative_letter(
+    words: &[ScoredWord],
+    known_info: &[GuessResult],
+) -> Option<(char, usize)> {
+    let tried: HashSet<char> = known_info
+        .iter()
+        .flat_map(|guess| guess.0.iter())
+        .filter_map(|c| match c {
+            Character::Yellow(t) | Character::Green(t) | Character::Red(t) => Some(*t),
+            Character::Wild | Character::Empty => None,
+        })
+        .collect();
+
+    ('a'..='z')
+        .filter(|letter| !tried.contains(letter))
+        .map(|letter| {
+            let count = words.iter().filter(|sw| sw.word.contains(letter)).count();
+            (letter, count)
+        })
+        .filter(|(_, count)| *count > 0 && *count < words.len())
+        // the distance from half is doubled to keep it whole
+        .min_by_key(|(letter, count)| ((2 * count).abs_diff(words.len()), *letter))
+}
+
+/// Just the latest clue, which is all a round's candidates need filtering by
+/// since they were whittled down by the earlier clues already. The whole
+/// history is still what the scoring looks at.
+fn newest_clue(known_info: &[GuessResult]) -> &[GuessResult] {
+    &known_info[known_info.len().saturating_sub(1)..]
+}
+
+/// Every strategy, in the order they're tested
+const STRATEGIES: [Strategy; 6] = [
+    Strategy::FrequencySimple,
+    Strategy::FrequencyPositionAware,
+    Strategy::Random,
+    Strategy::Entropy,
+    Strategy::Minimax,
+    Strategy::FrequencyPositionalWord,
+];
+
+/// A fresh rng for one random choice, seeded from `--seed` if it was given.
+/// Each choice gets its own, so drawing from one can't shift another's draws
+fn seeded_rng(args: &Args) -> StdRng {
+    match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Reads the letters for green, yellow and grey tiles, like `GYB`, which have
+/// to be three different ones
+fn parse_colors(letters: &str) -> Result<ClueLetters, String> {
+    let letters: Vec<char> = letters.chars().collect();
+    let [green, yellow, grey] = letters[..] else {
+        return Err("expected three letters, for green, yellow and grey, like GYB".to_string());
+    };
+    ClueLetters::new(green, yellow, grey).ok_or_else(|| {
+        "the letters for green, yellow and grey have to be different, and * is taken by wildcards"
+            .to_string()
+    })
+}
+
+/// Reads a guess budget, which has to leave room for at least one guess after
+/// the opener
+fn parse_guess_budget(n: &str) -> Result<usize, String> {
+    match n.parse::<usize>() {
+        Ok(n) if n >= 2 => Ok(n),
+        _ => Err("expected a whole number of guesses, at least 2".to_string()),
+    }
+}
+
+/// Reads a strategy name as given on the command line, like `frequency-simple`
+fn parse_strategy(name: &str) -> Result<Strategy, String> {
+    let name = name.replace(['-', '_'], "").to_lowercase();
+    STRATEGIES
+        .into_iter()
+        .find(|s| format!("{:?}", s).to_lowercase() == name)
+        .ok_or_else(|| {
+            "expected one of frequency-simple, frequency-position-aware, random, entropy, minimax or frequency-positional-word"
+                .to_string()
+        })
+}
+
+/// Reads a scorer's name for `--scorer`
+fn parse_scorer(name: &str) -> Result<Scorer, String> {
+    Scorer::parse(&name.to_lowercase())
+        .ok_or_else(|| "expected one of frequency, entropy or minimax".to_string())
+}
+
+/// Reads a pair of openers given as `first,second`
+fn parse_opener(pair: &str) -> Result<(String, String), String> {
+    let Some((first, second)) = pair.split_once(',') else {
+        return Err("expected two words separated by a comma, like slate,chord".to_string());
+    };
+    let (first, second) = (first.trim().to_lowercase(), second.trim().to_lowercase());
+    if first.len() != second.len() {
+        return Err(format!("{} and {} aren't the same length", first, second));
+    }
+    Ok((first, second))
+}
+
+/// Sets each word's starting score to its answer frequency, read from lines of
+/// `word count`. Words missing from the file keep a score of 1.
+fn apply_answer_frequencies(words: &mut [ScoredWord], contents: &str) {
+    let mut frequencies: HashMap<&str, usize> = HashMap::new();
+    for (n, line) in contents.lines().enumerate() {
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next().map(str::parse::<usize>)) {
+            (Some(word), Some(Ok(count))) => {
+                frequencies.insert(word, count);
+            }
+            (None, _) => {}
+            _ => warn!("Skipping invalid answer frequency on line {}", n + 1),
+        }
+    }
+
+    for sw in words.iter_mut() {
+        if let Some(count) = frequencies.get(sw.word.as_str()) {
+            sw.score = *count;
+        }
+    }
+}
+
+/// Keeps only the words that also appear in the whitespace-separated `subset`,
+/// warning about any words in the subset that aren't in the list
+fn intersect_words(words: Vec<ScoredWord>, subset: &str) -> Vec<ScoredWord> {
+    let subset: HashSet<&str> = subset.split_whitespace().collect();
+    let known: HashSet<&str> = words.iter().map(|sw| sw.word.as_str()).collect();
+
+    let mut missing: Vec<&str> = subset.difference(&known).copied().collect();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        warn!(
+            "Ignoring {} {} not in the word list: {}",
+            missing.len(),
+            if missing.len() == 1 { "word" } else { "words" },
+            missing.join(", ")
+        );
+    }
+
+    words
+        .into_iter()
+        .filter(|sw| subset.contains(sw.word.as_str()))
+        .collect()
+}
+
+/// Sorts the words and drops repeats of any, keeping the highest weight given,
+/// so the same words make the same list whatever order they came in. The caches
+/// look words up by where they are in the list, so it has to be the same order
+/// every time. Returns how many repeats were dropped.
+fn normalise_word_list(words: &mut Vec<ScoredWord>) -> usize {
+    let before = words.len();
+    // heaviest first within each word, so that's the one kept
+    words.sort_by(|a, b| a.word.cmp(&b.word).then(b.score.cmp(&a.score)));
+    words.dedup_by(|later, first| later.word == first.word);
+    before - words.len()
+}
+
+/// Reads a word list with one word per line, optionally followed by a weight
+/// saying how likely it is to be the answer. Words without a weight get 1.
+/// Words the solver can't play are left out, which `validate_word_list` warns
+/// about.
+fn read_word_list(contents: &str) -> Vec<ScoredWord> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(ScoredWord {
+                word: fields.next()?.to_string(),
+                score: fields.next().and_then(|w| w.parse().ok()).unwrap_or(1),
+            })
+        })
+        .filter(|sw| playable(&sw.word))
+        .collect()
+}
+
+/// Whether the solver can play a word: every clue has five tiles, so the word
+/// has to be five letters long, and ascii so they line up byte for byte
+fn playable(word: &str) -> bool {
+    word.len() == 5 && word.is_ascii()
+}
+
+/// Checks a word list read from a file has exactly one word on every line,
+/// with an optional positive weight after it, and at least one word the solver
+/// can play. Words it can't play are skipped with a warning.
+fn validate_word_list(contents: &str) -> Result<(), String> {
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut unplayable = 0;
+    for (n, line) in contents.lines().enumerate() {
+        let mut fields = line.split_whitespace();
+        let Some(word) = fields.next() else {
+            return Err(format!("line {} is empty", n + 1));
+        };
+        if let Some(weight) = fields.next() {
+            if !matches!(weight.parse::<usize>(), Ok(weight) if weight > 0) {
+                return Err(format!(
+                    "line {} has a weight that isn't a positive whole number",
+                    n + 1
+                ));
+            }
+        }
+        if fields.next().is_some() {
+            return Err(format!("line {} has more than one word", n + 1));
+        }
+        if playable(word) {
+            continue;
+        }
+        unplayable += 1;
+        *lengths.entry(word.chars().count()).or_default() += 1;
+    }
+    if contents.lines().count() == unplayable {
+        return Err("there are no 5 letter words in it".to_string());
+    }
+    if unplayable > 0 {
+        warn!(
+            "Skipping {} {} that {} 5 ascii letters: {}",
+            unplayable,
+            if unplayable == 1 { "word" } else { "words" },
+            if unplayable == 1 { "isn't" } else { "aren't" },
+            lengths
+                .iter()
+                .map(|(length, count)| format!("{} with {} letters", count, length))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// The Shannon entropy, in bits, of each position's letter distribution. Higher
+/// means the letter in that position is harder to predict.
+fn positional_entropy(words: &[ScoredWord]) -> [f64; 5] {
+    positional_frequencies(words).map(|counts| {
+        let total: usize = counts.iter().sum();
+        counts
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum()
+    })
+}
+
+/// Prints just the opener, so scripts can use it as it is, or with the
+/// strategy it came from as JSON
+fn print_opener(strategy: Strategy, opener: &str, json: bool) -> Result<(), SolverError> {
+    if json {
+        #[derive(serde::Serialize)]
+        struct Opener<'a> {
+            strategy: String,
+            opener: &'a str,
+        }
+        let opener = Opener {
+            strategy: format!("{:?}", strategy),
+            opener,
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&opener).map_err(SolverError::serialise("opener"))?
+        );
+        return Ok(());
+    }
+
+    println!("{}", opener);
+    Ok(())
+}
+
+/// Prints the entropy of each position, as a table or as JSON
+fn print_entropy_profile(words: &[ScoredWord], json: bool) -> Result<(), SolverError> {
+    let entropy = positional_entropy(words);
+
+    if json {
+        #[derive(serde::Serialize)]
+        struct PositionEntropy {
+            position: usize,
+            entropy: f64,
+        }
+        let positions: Vec<PositionEntropy> = entropy
+            .iter()
+            .enumerate()
+            .map(|(i, entropy)| PositionEntropy {
+                position: i + 1,
+                entropy: *entropy,
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&positions)
+                .map_err(SolverError::serialise("entropy profile"))?
+        );
+        return Ok(());
+    }
+
+    // a position where every letter is equally likely is as uncertain as it gets
+    let max_entropy = 26f64.log2();
+    for (i, entropy) in entropy.iter().enumerate() {
+        println!(
+            "  {} {} bits {}",
+            format!("{}:", i + 1).black(),
+            entropy.smooth_str(),
+            "█"
+                .repeat((40.0 * entropy / max_entropy).round() as usize)
+                .magenta()
+        );
+    }
+    Ok(())
+}
+
+/// A letter the word list has fewer than the minimum words for, either in one
+/// position or, with no position, anywhere at all
+#[derive(serde::Serialize)]
+struct CoverageGap {
+    letter: char,
+    position: Option<usize>,
+    words: usize,
+}
+
+/// Finds the letters and letter positions with fewer than `min` words. A
+/// letter that's rare everywhere is reported once, not once per position.
+fn coverage_gaps(words: &[ScoredWord], min: usize) -> Vec<CoverageGap> {
+    let frequencies = positional_frequencies(words);
+    let mut gaps = vec![];
+    for letter in 0..26 {
+        let c = (letter as u8 + 97) as char;
+        // counted the same way as the positions, so by answer frequency if loaded
+        let total: usize = words
+            .iter()
+            .filter(|sw| sw.word.contains(c))
+            .map(|sw| sw.score)
+            .sum();
+        if total < min {
+            gaps.push(CoverageGap {
+                letter: c,
+                position: None,
+                words: total,
+            });
+            continue;
+        }
+        for (i, counts) in frequencies.iter().enumerate() {
+            if counts[letter] < min {
+                gaps.push(CoverageGap {
+                    letter: c,
+                    position: Some(i + 1),
+                    words: counts[letter],
+                });
+            }
+        }
+    }
+    gaps
+}
+
+/// Prints coverage gaps as a list, or as JSON
+fn print_coverage_gaps(gaps: &[CoverageGap], json: bool) -> Result<(), SolverError> {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(gaps).map_err(SolverError::serialise("coverage gaps"))?
+        );
+        return Ok(());
+    }
+
+    if gaps.is_empty() {
+        println!("{}", "No coverage gaps found".green());
+        return Ok(());
+    }
+    for gap in gaps {
+        let place = match gap.position {
+            Some(position) => format!("in position {}", position),
+            None => "anywhere".to_string(),
+        };
+        if gap.words == 0 {
+            println!(
+                "  No words with '{}' {}",
+                gap.letter.to_string().magenta(),
+                place
+            );
+        } else {
+            println!(
+                "  Only {} {} with '{}' {}",
+                gap.words,
+                if gap.words == 1 { "word" } else { "words" },
+                gap.letter.to_string().magenta(),
+                place
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A line read from the user at a clue prompt
+enum Input {
+    /// Letters for the current colour, padded with dashes
+    Letters(String),
+    Command(Command),
+    /// Takes back the previous clue so it can be entered again
+    Undo,
+}
+
+/// Commands that can be typed at any clue prompt instead of letters
+enum Command {
+    /// Shows how a word would split the remaining candidates, without playing it
+    Try(String),
+    /// Prints the most likely answers and ends the session
+    GiveUp,
+    /// Switches how the candidates are ranked from the next suggestion on
+    Strategy(String),
+    /// Shows which candidates would be left if a word got a given clue, without
+    /// recording it
+    Assume(String, String),
+    /// Lists every remaining candidate with how likely it is, a page at a time
+    Show,
+}
+
+impl Command {
+    /// Parses a command, returning `None` if the input isn't one
+    fn parse(input: &str) -> Option<Command> {
+        let (name, arg) = input.split_once(' ').unwrap_or((input, ""));
+        match (name, arg.trim()) {
+            ("try", word) if !word.is_empty() => Some(Command::Try(word.to_string())),
+            ("giveup", "") => Some(Command::GiveUp),
+            ("show", "") => Some(Command::Show),
+            ("strategy", name) if !name.is_empty() => Some(Command::Strategy(name.to_string())),
+            ("assume", rest) => match rest.split_whitespace().collect::<Vec<&str>>()[..] {
+                [word, pattern] => Some(Command::Assume(word.to_string(), pattern.to_string())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Runs a command against the remaining candidates. Commands never change
+    /// the known info, so apart from `giveup`, the prompt carries on where it
+    /// was afterwards. Listings are fitted to the terminal, and patterns are read
+    /// with the letters from `--colors`.
+    fn run(&self, words: &[ScoredWord], scorer: &mut Scorer, args: &Args) {
+        let width = terminal_width(args);
+        match self {
+            Command::Try(word) => {
+                if word.len() != 5 || !word.chars().all(|c| c.is_ascii_lowercase()) {
+                    println!("Please enter a 5 letter word to try.");
+                    return;
+                }
+                println!("{}", describe_try(word, words));
+            }
+            Command::GiveUp => {
+                // every word sharing the top score is equally likely
+                let top_score = words.first().map_or(0, |sw| sw.score);
+                let tied = words.iter().take_while(|sw| sw.score == top_score).count();
+                let likeliest: Vec<String> = words
+                    .iter()
+                    .take(tied.min(5))
+                    .map(|sw| sw.word.blue().to_string())
+                    .collect();
+                if tied == 1 {
+                    println!("Giving up. The word is most likely {}!", likeliest[0]);
+                } else if tied > likeliest.len() {
+                    println!(
+                        "Giving up. The word is equally likely to be {} or {} others",
+                        likeliest.join(", "),
+                        tied - likeliest.len()
+                    );
+                } else {
+                    println!(
+                        "Giving up. The word is most likely one of {}",
+                        likeliest.join(", ")
+                    );
+                }
+                std::process::exit(0);
+            }
+            Command::Strategy(name) => match Scorer::parse(name) {
+                Some(new_scorer) => {
+                    *scorer = new_scorer;
+                    println!(
+                        "Using {} scoring from the next suggestion",
+                        format!("{:?}", new_scorer).magenta()
+                    );
+                }
+                None => println!(
+                    "Unknown strategy {}, try frequency, entropy or minimax.",
+                    name
+                ),
+            },
+            Command::Assume(word, pattern) => {
+                println!("{}", describe_assumption(word, pattern, words, args.colors));
+            }
+            Command::Show => {
+                // in the order they're ranked, with the same likelihoods as the
+                // suggestions
+                let total_score = words.iter().map(|sw| sw.score).sum::<usize>();
+                let cells = words
+                    .iter()
+                    .map(|sw| {
+                        (
+                            sw.word.clone(),
+                            format!(
+                                " ({}%)",
+                                (100.0 * (sw.score as f64) / (total_score as f64)).smooth_str()
+                            ),
+                        )
+                    })
+                    .collect::<Vec<(String, String)>>();
+                println!(
+                    "{} remaining {}:",
+                    words.len(),
+                    if words.len() == 1 { "word" } else { "words" }
+                );
+                // each page is laid out on its own, so reading down its columns
+                // and then on to the next page keeps to the ranking
+                let cell_width = cells
+                    .iter()
+                    .map(|(word, detail)| word.chars().count() + detail.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    + COLUMN_GAP;
+                let page_size =
+                    SHOW_PAGE_LINES * (width.saturating_sub(COLUMN_GAP) / cell_width).max(1);
+                for (i, page) in cells.chunks(page_size).enumerate() {
+                    if i > 0 {
+                        print!(
+                            "-- {} more, enter to see them or q to stop --",
+                            cells.len() - i * page_size
+                        );
+                        if read_stdin_line() == "q" {
+                            break;
+                        }
+                    }
+                    println!("{}", format_columns(page, width));
+                }
+            }
+        }
+    }
+}
+
+/// Handles user input for a guess result. The whole result can be given at
+/// once as a pattern like `gybrr`, or left blank to give each colour in turn.
+/// Returns `None` if the user asks to undo the previous result instead
+fn get_guess_result(
+    last_guess: &String,
+    words: &[ScoredWord],
+    args: &Args,
+    scorer: &mut Scorer,
+    palette: Palette,
+) -> Option<GuessResult> {
+    println!(
+        "Enter the result, one letter per tile ({} for green, {} for yellow, {} or {} for letters not in the word), or press enter to give each colour separately:",
+        palette.green(&args.colors.green.to_string()),
+        palette.yellow(&args.colors.yellow.to_string()),
+        palette.red(&args.colors.grey.to_string()),
+        palette.red("r")
+    );
+    let buffer = loop {
+        print!(">> ");
+        let input = read_stdin_line();
+        let input = input.as_str();
+
+        if input == "exit" {
+            println!("Exiting...");
+            std::process::exit(0);
+        }
+        if input == "undo" {
+            return None;
+        }
+        if let Some(command) = Command::parse(input) {
+            command.run(words, scorer, args);
+            continue;
+        }
+        if input.is_empty() {
+            break get_guess_result_by_colour(last_guess, words, args, scorer, palette)?;
+        }
+        match parse_pattern_with(last_guess, input, args.colors) {
+            Ok(result) => break result,
+            Err(e) => println!("{}", format!("Couldn't read that result: {}", e).red()),
+        }
+    };
+
+    print!("You have entered {}. Correct? (y): ", buffer.paint(palette));
+    let key = read_stdin_line();
+
+    if key == "y" || key == "" {
+        Some(buffer)
+    } else {
+        get_guess_result(last_guess, words, args, scorer, palette)
+    }
+}
+
+/// Asks for the yellow, red and green characters of a guess result one colour
+/// at a time
+fn get_guess_result_by_colour(
+    last_guess: &String,
+    words: &[ScoredWord],
+    args: &Args,
+    scorer: &mut Scorer,
+    palette: Palette,
+) -> Option<GuessResult> {
+    let mut buffer = GuessResult([Character::Empty; 5]);
+
+    for t in ["yellow", "red", "green"] {
+        if buffer.0.iter().all(|c| !matches!(c, Character::Empty)) {
+            break;
+        } else if t == "green" {
+            let last_guess: Vec<char> = last_guess.chars().collect();
+            // replace all empty characters with green characters from the previous guess
+            for (i, c) in buffer.0.iter_mut().enumerate() {
+                if matches!(c, Character::Empty) {
+                    *c = Character::Green(last_guess[i]);
+                }
+            }
+            break;
+        }
+        println!(
+            "Enter the {t} characters. For non-{t} characters, use '-':",
+            t = match t {
+                "yellow" => palette.yellow("yellow"),
+                "red" => palette.red("red"),
+                "green" => palette.green("green"),
+                _ => unreachable!(),
+            }
+        );
+        let input = loop {
+            match read_line(5, last_guess, args.truncate_long) {
+                Input::Letters(input) => break input,
+                Input::Command(command) => command.run(words, scorer, args),
+                Input::Undo => return None,
+            }
+        };
+
+        if input.len() == 0 {
+            // special case for empty input, we assume all empty characters are of the given
+            // type
+            for (i, c) in buffer.0.iter_mut().enumerate() {
+                if matches!(c, Character::Empty) {
+                    let c2 = last_guess.chars().nth(i).unwrap();
+                    *c = match t {
+                        "yellow" => Character::Yellow(c2),
+                        "red" => Character::Red(c2),
+                        "green" => Character::Green(c2),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        } else {
+            for (i, c) in input.chars().enumerate() {
+                if c == '*' {
+                    buffer.0[i] = Character::Wild;
+                } else if c != '-' {
+                    buffer.0[i] = match t {
+                        "yellow" => Character::Yellow(c),
+                        "red" => Character::Red(c),
+                        "green" => Character::Green(c),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+    }
+
+    Some(buffer)
+}
+
+/// Flushes any prompt and reads a trimmed line from stdin. Once stdin is closed
+/// there's no more input to wait for, so the solver exits as if told to. Input
+/// that can't be read, like bytes that aren't UTF-8 from a pipe, ends it too,
+/// since there's no telling where the next line starts.
+fn read_stdin_line() -> String {
+    std::io::stdout().flush().unwrap();
+    let mut line = String::new();
+    match std::io::stdin().read_line(&mut line) {
+        Ok(0) => {
+            println!("\nExiting...");
+            std::process::exit(0);
+        }
+        Ok(_) => line.trim().to_string(),
+        Err(e) => {
+            println!("\nCouldn't read the input ({}), exiting...", e);
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Reads a line from stdin, and returns it as either a command or letters. If
+/// the letters are not the expected length, the user is prompted to try again,
+/// unless `truncate_long` is set and there are too many of them.
+fn read_line(expected_length: usize, guess: &String, truncate_long: bool) -> Input {
+    print!(">> ");
+    let mut buffer = read_stdin_line();
+
+    if buffer == "exit" {
+        println!("Exiting...");
+        std::process::exit(0);
+    }
+
+    if buffer == "undo" {
+        return Input::Undo;
+    }
+
+    if let Some(command) = Command::parse(&buffer) {
+        return Input::Command(command);
+    }
+
+    // ensure string is lowercase a-z, - or *
+    if !buffer.chars().all(|c| matches!(c, 'a'..='z' | '-' | '*')) {
+        println!("Please enter only lowercase letters, '-' or '*'.");
+        read_line(expected_length, guess, truncate_long)
+    } else if buffer.len() != expected_length {
+        if buffer.len() == 0 {
+            Input::Letters(buffer)
+        } else if buffer.len() < expected_length {
+            buffer.push_str(&"-".repeat(expected_length - buffer.len()));
+            Input::Letters(buffer)
+        } else if truncate_long {
+            buffer.truncate(expected_length);
+            println!(
+                "{}",
+                format!("Too many characters, only using {}", buffer).yellow()
+            );
+            Input::Letters(buffer)
+        } else {
+            println!("Please enter exactly {} characters.", expected_length);
+            read_line(expected_length, guess, truncate_long)
+        }
+    } else {
+        Input::Letters(buffer)
+    }
+}
+
+/// How the solver got on against a single answer
+struct SolveTrace {
+    /// Every guess made, ending with the answer if it was found
+    guesses: Vec<String>,
+    solved: bool,
+}
+
+/// Plays the solver against a known answer starting from `openers`, played in
+/// turn whatever their clues, then taking its suggestion each round the way a
+/// session would, ranked by `ranking` with probes for anagrams included. The answer counts as found if it's suggested within
+/// `max_guesses` guesses. Probes come from the `allowed` guesses, except in `hard` mode, where
+/// only words that fit every clue so far are suggested, so probes have to come
+/// from the candidates too. Clues are looked up in `patterns` where it has them.
+#[allow(clippy::too_many_arguments)]
+fn solve_word(
+    words: &[ScoredWord],
+    allowed: &[ScoredWord],
+    openers: &[&str],
+    answer: &String,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    patterns: Option<&Patterns>,
+) -> SolveTrace {
+    let mut possible_words = vec![];
+    let mut guesses = vec![openers[0].to_string()];
+    if openers[0] == answer {
+        return SolveTrace {
+            guesses,
+            solved: true,
+        };
+    }
+    let mut known_info = vec![];
+    for _ in 1..max_guesses {
+        let guess = guesses.last().unwrap();
+        known_info.push(calculate_guess_result(answer, guess));
+        // the first clue filters the whole list, which isn't worth copying first
+        let first = known_info.len() == 1;
+        let candidates = if first { words } else { &possible_words[..] };
+        let looked_up = patterns.and_then(|p| match first {
+            true => p.filter_list(words, guess, answer),
+            false => p.filter(candidates, guess, answer),
+        });
+        possible_words = match looked_up {
+            Some(filtered) => filtered,
+            None => filter_using_known_info(candidates, &known_info),
+        };
+        possible_words = ranking.rank(possible_words, &known_info);
+        let round = known_info.len();
+        // the answer always fits its own clues, but if the rules ever disagree
+        // there's nothing left to guess
+        let Some(guess) = choose_next_guess(&possible_words, allowed, openers, round, hard) else {
+            break;
+        };
+        guesses.push(guess.word().to_string());
+        if guesses.last() == Some(answer) {
+            return SolveTrace {
+                guesses,
+                solved: true,
+            };
+        }
+    }
+    SolveTrace {
+        guesses,
+        solved: false,
+    }
+}
+
+/// One round of a recorded session: the clue a guess got, and what the solver
+/// suggested after it
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FixtureStep {
+    guess: String,
+    /// The clue, spelt out as `GuessResult::pattern` does
+    pattern: String,
+    expected_next: String,
+}
+
+/// A recorded solver session against a known answer
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Fixture {
+    answer: String,
+    steps: Vec<FixtureStep>,
+}
+
+/// Solves `count` answers picked with a seeded rng from `first_guess`, recording
+/// each round. The same list, opener and seed always give the same fixtures.
+fn gen_fixtures(
+    words: &[ScoredWord],
+    first_guess: &str,
+    ranking: &Ranking,
+    seed: u64,
+    count: usize,
+    max_guesses: usize,
+) -> Vec<Fixture> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    words
+        .choose_multiple(&mut rng, count)
+        .map(|sw| {
+            let trace = solve_word(
+                words,
+                words,
+                &[first_guess],
+                &sw.word,
+                ranking,
+                false,
+                max_guesses,
+                None,
+            );
+            let steps = trace
+                .guesses
+                .windows(2)
+                .map(|pair| FixtureStep {
+                    guess: pair[0].clone(),
+                    pattern: calculate_guess_result(&sw.word, &pair[0]).pattern(),
+                    expected_next: pair[1].clone(),
+                })
+                .collect();
+            Fixture {
+                answer: sw.word.clone(),
+                steps,
+            }
+        })
+        .collect()
+}
+
+/// How many words took each number of guesses to solve, from 1 up to the
+/// guess budget, and how many couldn't be solved at all
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct GuessHistogram {
+    solved: Vec<u32>,
+    failed: u32,
+}
+
+impl GuessHistogram {
+    /// An empty histogram for words solved within `max_guesses`
+    fn new(max_guesses: usize) -> Self {
+        GuessHistogram {
+            solved: vec![0; max_guesses],
+            failed: 0,
+        }
+    }
+
+    /// How many guesses a word had to be solved in to count
+    fn max_guesses(&self) -> usize {
+        self.solved.len()
+    }
+
+    fn record(mut self, trace: &SolveTrace) -> Self {
+        if trace.solved {
+            self.solved[trace.guesses.len() - 1] += 1;
+        } else {
+            self.failed += 1;
+        }
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (count, other_count) in self.solved.iter_mut().zip(other.solved) {
+            *count += other_count;
+        }
+        self.failed += other.failed;
+        self
+    }
+
+    /// Builds the histogram with each thread counting its own share of the
+    /// traces, merging the counts at the end
+    fn from_traces(traces: &[SolveTrace], max_guesses: usize) -> Self {
+        traces
+            .par_iter()
+            .fold(|| GuessHistogram::new(max_guesses), GuessHistogram::record)
+            .reduce(|| GuessHistogram::new(max_guesses), GuessHistogram::merge)
+    }
+
+    /// How many words were solved at all
+    fn solved_count(&self) -> u32 {
+        self.solved.iter().sum()
+    }
+
+    /// The mean number of guesses taken over the words that were solved
+    fn average_guesses(&self) -> f64 {
+        let solved: u32 = self.solved.iter().sum();
+        let guesses: u32 = self
+            .solved
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (i as u32 + 1) * count)
+            .sum();
+        if solved == 0 {
+            return 0.0;
+        }
+        guesses as f64 / solved as f64
+    }
+
+    /// The share of all words solved within 1, 2, ... up to `max_guesses`
+    /// guesses. The last entry is the share solved at all.
+    fn cdf(&self) -> Vec<f64> {
+        let total = (self.solved.iter().sum::<u32>() + self.failed) as f64;
+        self.solved
+            .iter()
+            .scan(0, |solved, count| {
+                *solved += count;
+                Some(*solved as f64 / total)
+            })
+            .collect()
+    }
+
+    /// Prints a bar for each guess count, scaled to the most common one
+    fn print(&self) {
+        let most = self
+            .solved
+            .iter()
+            .chain([&self.failed])
+            .max()
+            .copied()
+            .unwrap_or(0);
+        let bar = |count: u32| "█".repeat((40 * count).checked_div(most).unwrap_or(0) as usize);
+        for (i, count) in self.solved.iter().enumerate() {
+            println!(
+                "  {} {} {}",
+                format!("{}:", i + 1).black(),
+                bar(*count).green(),
+                count
+            );
+        }
+        println!(
+            "  {} {} {}",
+            "X:".black(),
+            bar(self.failed).red(),
+            self.failed
+        );
+    }
+}
+
+/// Plays the solver against every word on the list, starting from the same
+/// first guess, and returns how it got on with each in list order
+fn evaluate_first_guess(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    first_guess: &str,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    patterns: Option<&Patterns>,
+) -> Vec<SolveTrace> {
+    evaluate_openers(
+        words,
+        guesses,
+        &[first_guess],
+        ranking,
+        hard,
+        max_guesses,
+        patterns,
+    )
+}
+
+/// Like `evaluate_first_guess`, but playing each of `openers` in turn before
+/// the solver starts adapting
+fn evaluate_openers(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    openers: &[&str],
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    patterns: Option<&Patterns>,
+) -> Vec<SolveTrace> {
+    evaluate_openers_until(
+        words,
+        guesses,
+        openers,
+        ranking,
+        hard,
+        max_guesses,
+        None,
+        patterns,
+    )
+    .expect("no deadline was set")
+}
+
+/// Like `evaluate_openers`, but gives up and returns `None` if the deadline
+/// passes before every word is solved
+#[allow(clippy::too_many_arguments)]
+fn evaluate_openers_until(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    openers: &[&str],
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    deadline: Option<Instant>,
+    patterns: Option<&Patterns>,
+) -> Option<Vec<SolveTrace>> {
+    words
+        .par_iter()
+        .map(|sw| {
+            if matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+                None
+            } else {
+                Some(solve_word(
+                    words,
+                    guesses,
+                    openers,
+                    &sw.word,
+                    ranking,
+                    hard,
+                    max_guesses,
+                    patterns,
+                ))
+            }
+        })
+        .collect()
+}
+
+/// returns how many guesses each answer took with the given strategy, along
+/// with its first guess, or `None` if the deadline passed before it could
+/// finish. Only `words` are tried as answers, though the opener and probes can
+/// come from `guesses`
+#[allow(clippy::too_many_arguments)]
+fn test_strategy(
+    words: &Vec<ScoredWord>,
+    guesses: &[ScoredWord],
+    strategy: Strategy,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    deadline: Option<Instant>,
+    rng: &mut StdRng,
+    frequencies: &[[usize; 26]; 5],
+    patterns: &Patterns,
+) -> Option<(GuessHistogram, String)> {
+    let guess = get_first_guess_using(words, guesses, strategy, frequencies, rng);
+    let histogram = test_openers(
+        words,
+        guesses,
+        &[&guess],
+        ranking,
+        hard,
+        max_guesses,
+        deadline,
+        patterns,
+    )?;
+    Some((histogram, guess))
+}
+
+/// returns how many guesses each word took when opening with each of `openers`
+/// in turn, or `None` if the deadline passed before it could finish
+#[allow(clippy::too_many_arguments)]
+fn test_openers(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    openers: &[&str],
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    deadline: Option<Instant>,
+    patterns: &Patterns,
+) -> Option<GuessHistogram> {
+    let traces = evaluate_openers_until(
+        words,
+        guesses,
+        openers,
+        ranking,
+        hard,
+        max_guesses,
+        deadline,
+        Some(patterns),
+    )?;
+    Some(GuessHistogram::from_traces(&traces, max_guesses))
+}
+
+/// Solves every word on the list from the openers and prints
+/// how many were solvable, optionally exporting the per-word results as CSV.
+/// With `sample`, only that many randomly picked words are solved, and the
+/// solvable percentage comes with a 95% confidence interval.
+#[allow(clippy::too_many_arguments)]
+fn analyze(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    strategy: Strategy,
+    openers: &[&str],
+    ranking: &Ranking,
+    export_path: Option<&str>,
+    sample: Option<usize>,
+    rng: &mut StdRng,
+    hard: bool,
+    max_guesses: usize,
+    patterns: Option<&Patterns>,
+) -> Result<(), SolverError> {
+    let start = std::time::Instant::now();
+    let answers: Vec<&ScoredWord> = match sample {
+        Some(n) => words.choose_multiple(rng, n).collect(),
+        None => words.iter().collect(),
+    };
+    let traces: Vec<SolveTrace> = answers
+        .par_iter()
+        .map(|sw| {
+            solve_word(
+                words,
+                guesses,
+                openers,
+                &sw.word,
+                ranking,
+                hard,
+                max_guesses,
+                patterns,
+            )
+        })
+        .collect();
+    let histogram = GuessHistogram::from_traces(&traces, max_guesses);
+    let solvable = answers.len() - histogram.failed as usize;
+    // the same answers again under the other mode's rules, to compare
+    let other_solvable = answers
+        .par_iter()
+        .filter(|sw| {
+            solve_word(
+                words,
+                guesses,
+                openers,
+                &sw.word,
+                ranking,
+                !hard,
+                max_guesses,
+                patterns,
+            )
+            .solved
+        })
+        .count();
+    let (hard_solvable, normal_solvable) = if hard {
+        (solvable, other_solvable)
+    } else {
+        (other_solvable, solvable)
+    };
+    let rate = solvable as f64 / answers.len() as f64;
+
+    println!(
+        "{} {} strategy, opening with {}, solves {}/{} {}words within {} guesses ({}%{})",
+        format!("[{:?}]", start.elapsed()).black(),
+        format!("{:?}", strategy).magenta(),
+        openers.join(" then ").blue(),
+        solvable,
+        answers.len(),
+        if sample.is_some() { "sampled " } else { "" },
+        max_guesses,
+        (100.0 * rate).smooth_str(),
+        if sample.is_some() {
+            format!(
+                " ± {}%",
+                (100.0 * confidence_margin(rate, answers.len())).smooth_str()
+            )
+        } else {
+            String::new()
+        }
+    );
+    println!(
+        "  {} in normal mode, {} in hard mode",
+        normal_solvable, hard_solvable
+    );
+    histogram.print();
+
+    if let Some(path) = export_path {
+        let mut csv = String::from("word,guesses,solved\n");
+        for (sw, trace) in answers.iter().zip(&traces) {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                sw.word,
+                trace.guesses.len(),
+                trace.solved
+            ));
+        }
+        std::fs::write(path, csv).map_err(SolverError::io("write", path))?;
+        info!("Exported stats to {}", path);
+    }
+    Ok(())
+}
+
+/// How a benchmark run went
+#[derive(serde::Serialize)]
+struct BenchReport {
+    strategy: String,
+    opener: String,
+    words: usize,
+    #[serde(flatten)]
+    histogram: GuessHistogram,
+    average_guesses: f64,
+    /// The most guesses any solved word took
+    max_guesses: usize,
+    seconds: f64,
+    words_per_second: f64,
+    /// How long the same solves take working every clue out instead of looking
+    /// them up in the pattern matrix
+    seconds_without_patterns: f64,
+    speedup: f64,
+}
+
+/// Solves every word on the list from `openers`, timing how long it takes, then
+/// again without the pattern matrix to see how much it saves
+#[allow(clippy::too_many_arguments)]
+fn bench(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    strategy: Strategy,
+    openers: &[&str],
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    patterns: &Patterns,
+) -> BenchReport {
+    let start = Instant::now();
+    let histogram = test_openers(
+        words,
+        guesses,
+        openers,
+        ranking,
+        hard,
+        max_guesses,
+        None,
+        patterns,
+    )
+    .expect("no deadline was set");
+    let seconds = start.elapsed().as_secs_f64();
+    let start = Instant::now();
+    evaluate_openers(words, guesses, openers, ranking, hard, max_guesses, None);
+    let seconds_without_patterns = start.elapsed().as_secs_f64();
+    BenchReport {
+        strategy: format!("{:?}", strategy),
+        opener: openers.join(","),
+        words: words.len(),
+        average_guesses: histogram.average_guesses(),
+        max_guesses: histogram
+            .solved
+            .iter()
+            .rposition(|count| *count > 0)
+            .map_or(0, |i| i + 1),
+        histogram,
+        seconds,
+        words_per_second: words.len() as f64 / seconds,
+        seconds_without_patterns,
+        speedup: seconds_without_patterns / seconds,
+    }
+}
+
+/// Prints a benchmark report along with its histogram, or as JSON
+fn print_bench(report: &BenchReport, json: bool) -> Result<(), SolverError> {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(report).map_err(SolverError::serialise("benchmark"))?
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} {} strategy, opening with {}, solved {}/{} words within {} guesses",
+        format!("[{}s]", report.seconds.smooth_str()).black(),
+        report.strategy.magenta(),
+        report.opener.blue(),
+        report.histogram.solved_count(),
+        report.words,
+        report.histogram.max_guesses()
+    );
+    println!(
+        "  averaging {} guesses, at most {}, at {} words per second",
+        report.average_guesses.smooth_str(),
+        report.max_guesses,
+        report.words_per_second.round()
+    );
+    println!(
+        "  {}x faster than without the pattern matrix, which took {}s",
+        report.speedup.smooth_str(),
+        report.seconds_without_patterns.smooth_str()
+    );
+    report.histogram.print();
+    Ok(())
+}
+
+/// How well the solver does on the word list from one opener
+#[derive(serde::Serialize)]
+struct OpenerEvaluation {
+    opener: String,
+    solvable: usize,
+    average_guesses: f64,
+}
+
+/// Solves every word on the list from each opener in `contents`, one per line,
+/// and returns the results with the most solvable first. Openers that solve
+/// just as many are ordered by fewest guesses on average.
+fn eval_openers(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    contents: &str,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    patterns: &Patterns,
+) -> Vec<OpenerEvaluation> {
+    let mut evaluations = vec![];
+    for (n, line) in contents.lines().enumerate() {
+        let opener = line.trim().to_lowercase();
+        if opener.is_empty() {
+            continue;
+        }
+        if opener.len() != 5 || !opener.chars().all(|c| c.is_ascii_lowercase()) {
+            warn!("Skipping invalid opener on line {}", n + 1);
+            continue;
+        }
+        let traces = evaluate_first_guess(
+            words,
+            guesses,
+            &opener,
+            ranking,
+            hard,
+            max_guesses,
+            Some(patterns),
+        );
+        let histogram = GuessHistogram::from_traces(&traces, max_guesses);
+        evaluations.push(OpenerEvaluation {
+            solvable: words.len() - histogram.failed as usize,
+            average_guesses: histogram.average_guesses(),
+            opener,
+        });
+    }
+    evaluations.sort_by(|a, b| {
+        b.solvable
+            .cmp(&a.solvable)
+            .then(a.average_guesses.total_cmp(&b.average_guesses))
+            .then_with(|| a.opener.cmp(&b.opener))
+    });
+    evaluations
+}
+
+/// Prints opener evaluations as a table, or as JSON
+fn print_opener_evaluations(
+    evaluations: &[OpenerEvaluation],
+    json: bool,
+) -> Result<(), SolverError> {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(evaluations)
+                .map_err(SolverError::serialise("evaluations"))?
+        );
+        return Ok(());
+    }
+
+    println!("  {}", "Opener  Solvable  Average".black());
+    for evaluation in evaluations {
+        println!(
+            "  {}   {:<8}  {}",
+            evaluation.opener.blue(),
+            evaluation.solvable,
+            evaluation.average_guesses.smooth_str()
+        );
+    }
+    Ok(())
+}
+
+/// Half the width of the 95% confidence interval for a rate measured over `n`
+/// samples, using the normal approximation to the binomial
+fn confidence_margin(rate: f64, n: usize) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+    1.96 * (rate * (1.0 - rate) / n as f64).sqrt()
+}
+
+/// How many words contain each letter
+fn letter_frequencies(words: &[ScoredWord]) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for sw in words {
+        let letters: HashSet<char> = sw.word.chars().collect();
+        for c in letters {
+            if let Some(letter) = letter_index(c) {
+                counts[letter] += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Sums the frequencies of the distinct letters in the given words, so letters
+/// tested more than once only count once
+fn letter_coverage(frequencies: &[usize; 26], openers: &[&str]) -> usize {
+    let letters: HashSet<char> = openers.iter().flat_map(|o| o.chars()).collect();
+    letters
+        .iter()
+        .filter_map(|c| letter_index(*c))
+        .map(|letter| frequencies[letter])
+        .sum()
+}
+
+/// Finds the pair of words whose letters between them appear in the most
+/// words. Only the `top` words with the best coverage on their own are tried
+/// as the first of the pair, as trying every pair would take far too long on a
+/// full list. There's no pair on a list of fewer than two words.
+fn best_pair(words: &[ScoredWord], top: usize) -> Option<(String, String)> {
+    let frequencies = letter_frequencies(words);
+    let mut singles: Vec<(usize, &str)> = words
+        .iter()
+        .map(|sw| (letter_coverage(&frequencies, &[&sw.word]), sw.word.as_str()))
+        .collect();
+    singles.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    singles
+        .par_iter()
+        .take(top.max(1))
+        .filter_map(|(_, first)| {
+            // the second word only has to make up for what the first misses, so any
+            // word on the list is worth trying
+            words
+                .iter()
+                .filter(|sw| sw.word != *first)
+                .map(|sw| {
+                    let coverage = letter_coverage(&frequencies, &[first, &sw.word]);
+                    (coverage, first.to_string(), sw.word.clone())
+                })
+                .max_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(b.2.cmp(&a.2)))
+        .map(|(_, first, second)| (first, second))
+}
+
+/// Prints an opener pair with how much it covers compared to the first alone,
+/// and how many words are left on average after both
+fn print_pair(words: &[ScoredWord], first: &str, second: &str) {
+    let frequencies = letter_frequencies(words);
+    let mut buckets: HashMap<(u8, u8), usize> = HashMap::new();
+    for sw in words {
+        let key = (
+            pattern_code(first.as_bytes(), sw.word.as_bytes()),
+            pattern_code(second.as_bytes(), sw.word.as_bytes()),
+        );
+        *buckets.entry(key).or_insert(0) += 1;
+    }
+    let remaining =
+        buckets.values().map(|size| size * size).sum::<usize>() as f64 / words.len() as f64;
+
+    println!(
+        "Best pair is {} then {}, sharing {} letters",
+        first.blue(),
+        second.blue(),
+        first.chars().filter(|c| second.contains(*c)).count()
+    );
+    println!(
+        "  {} letter coverage, up from {} for {} alone",
+        letter_coverage(&frequencies, &[first, second]),
+        letter_coverage(&frequencies, &[first]),
+        first
+    );
+    println!(
+        "  {} words left on average after both",
+        remaining.smooth_str()
+    );
+}
+
+/// Prints the chance of solving within each number of guesses, or as JSON
+fn print_solve_cdf(
+    opener: &str,
+    histogram: &GuessHistogram,
+    json: bool,
+) -> Result<(), SolverError> {
+    let cdf = histogram.cdf();
+
+    if json {
+        #[derive(serde::Serialize)]
+        struct SolveWithin {
+            guesses: usize,
+            probability: f64,
+        }
+        let within: Vec<SolveWithin> = cdf
+            .iter()
+            .enumerate()
+            .map(|(i, probability)| SolveWithin {
+                guesses: i + 1,
+                probability: *probability,
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&within).map_err(SolverError::serialise("solve CDF"))?
+        );
+        return Ok(());
+    }
+
+    println!("Chance of solving from {} within", opener.blue());
+    for (i, probability) in cdf.iter().enumerate() {
+        println!(
+            "  {} {}% {}",
+            format!("{}:", i + 1).black(),
+            (100.0 * probability).smooth_str(),
+            "█".repeat((40.0 * probability).round() as usize).green()
+        );
+    }
+    Ok(())
+}
+
+/// Groups the words by the clue `opener` would get against each, returning the
+/// `top` most common clues along with how many words share each and the
+/// solver's second guess for it
+fn cheat_sheet(
+    words: &[ScoredWord],
+    opener: &String,
+    ranking: &Ranking,
+    top: usize,
+) -> Vec<(GuessResult, usize, String)> {
+    let mut buckets: HashMap<u8, Vec<ScoredWord>> = HashMap::new();
+    for sw in words {
+        buckets
+            .entry(pattern_code(opener.as_bytes(), sw.word.as_bytes()))
+            .or_default()
+            .push(sw.clone());
+    }
+
+    let mut buckets: Vec<(u8, Vec<ScoredWord>)> = buckets.into_iter().collect();
+    buckets.sort_by(|(a_code, a), (b_code, b)| b.len().cmp(&a.len()).then(a_code.cmp(b_code)));
+
+    buckets
+        .into_iter()
+        .take(top)
+        .map(|(code, bucket)| {
+            let mut known_info = vec![GuessResult::from_code(code, opener)];
+            let count = bucket.len();
+            // picked the same way a solve picks it, anagram probes and all
+            let candidates = ranking.rank(bucket, &known_info);
+            let second_guess = next_guess(&candidates, words, &[opener], 1, false);
+            (known_info.pop().unwrap(), count, second_guess)
+        })
+        .collect()
+}
+
+/// Prints the cheat sheet for an opener as a table
+fn print_cheat_sheet(
+    words: &[ScoredWord],
+    opener: &String,
+    ranking: &Ranking,
+    top: usize,
+    palette: Palette,
+) {
+    println!("Cheat sheet for {}", opener.blue());
+    println!("  {}", "Clue   Words  Next".black());
+    for (clue, count, second_guess) in cheat_sheet(words, opener, ranking, top) {
+        println!(
+            "  {}  {:<5}  {}",
+            clue.paint(palette),
+            count,
+            second_guess.blue()
+        );
+    }
+}
+
+/// A reproducible record of how well an opener does on a word list, which
+/// anyone with the same list can recompute with `verify-claim`
+#[derive(serde::Serialize, serde::Deserialize)]
+struct OpenerClaim {
+    list_digest: String,
+    strategy: String,
+    opener: String,
+    /// How many words on the list the solver finds within `max_guesses`
+    solvable: usize,
+    max_guesses: usize,
+    crate_version: String,
+    /// Unkeyed hash of all the other fields, so accidental edits are caught.
+    /// Anyone can recompute it, so it proves nothing about who made the claim
+    checksum: String,
+}
+
+impl OpenerClaim {
+    fn new(
+        words: &[ScoredWord],
+        all_words: &[ScoredWord],
+        list_digest: &str,
+        strategy: Strategy,
+        opener: &str,
+        ranking: &Ranking,
+        max_guesses: usize,
+    ) -> Self {
+        // claims are always for normal mode, so they can be checked the same way
+        let solvable =
+            evaluate_first_guess(words, all_words, opener, ranking, false, max_guesses, None)
+                .iter()
+                .filter(|trace| trace.solved)
+                .count();
+        let mut claim = OpenerClaim {
+            list_digest: list_digest.to_string(),
+            strategy: format!("{:?}", strategy),
+            opener: opener.to_string(),
+            solvable,
+            max_guesses,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            checksum: String::new(),
+        };
+        claim.checksum = claim.compute_checksum();
+        claim
+    }
+
+    fn compute_checksum(&self) -> String {
+        hash(
+            format!(
+                "{}|{}|{}|{}|{}|{}",
+                self.list_digest,
+                self.strategy,
+                self.opener,
+                self.solvable,
+                self.max_guesses,
+                self.crate_version
+            )
+            .as_bytes(),
+        )
+        .to_hex()
+        .to_string()
+    }
+
+    /// Recomputes the claim against a word list and guess budget, returning why
+    /// it doesn't hold if it doesn't
+    fn verify(
+        &self,
+        words: &Vec<ScoredWord>,
+        all_words: &[ScoredWord],
+        frequencies: &[[usize; 26]; 5],
+        list_digest: &str,
+        ranking: &Ranking,
+        max_guesses: usize,
+    ) -> Result<(), String> {
+        if self.checksum != self.compute_checksum() {
+            return Err("checksum doesn't match the claim".to_string());
+        }
+        if self.list_digest != list_digest {
+            return Err(format!("claim is for wordset {}", self.list_digest));
+        }
+        if self.max_guesses != max_guesses {
+            return Err(format!(
+                "claim allows {} guesses, but the solver allows {}",
+                self.max_guesses, max_guesses
+            ));
+        }
+        let strategy = STRATEGIES
+            .into_iter()
+            .chain([Strategy::Annealed])
+            .find(|s| format!("{:?}", s) == self.strategy)
+            .ok_or(format!("unknown strategy {}", self.strategy))?;
+        // random and annealed openers can't be reproduced, but how well they do
+        // still can
+        if !matches!(strategy, Strategy::Random | Strategy::Annealed) {
+            // the strategy isn't random, so the rng is never drawn from
+            let opener = get_first_guess_using(
+                words,
+                all_words,
+                strategy,
+                frequencies,
+                &mut StdRng::seed_from_u64(0),
+            );
+            if opener != self.opener {
+                return Err(format!(
+                    "{:?} opens with {}, not {}",
+                    strategy, opener, self.opener
+                ));
+            }
+        }
+        if self.crate_version != env!("CARGO_PKG_VERSION") {
+            warn!(
+                "Claim was made with solver {}, this is {}",
+                self.crate_version,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+
+        let recomputed = OpenerClaim::new(
+            words,
+            all_words,
+            list_digest,
+            strategy,
+            &self.opener,
+            ranking,
+            max_guesses,
+        );
+        if recomputed.solvable != self.solvable {
+            return Err(format!(
+                "{} solves {} words, not {}",
+                self.opener, recomputed.solvable, self.solvable
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Prints whether the solver finds `answer` from `openers`, along with the
+/// clue each of its guesses would get
+#[allow(clippy::too_many_arguments)]
+fn print_solvable(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    openers: &[&str],
+    answer: &String,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    md_path: Option<&str>,
+    palette: Palette,
+) -> Result<(), SolverError> {
+    if !words.iter().any(|sw| sw.word == *answer) {
+        return Err(SolverError::NotInWordList(answer.clone()));
+    }
+
+    let trace = solve_word(
+        words,
+        guesses,
+        openers,
+        answer,
+        ranking,
+        hard,
+        max_guesses,
+        None,
+    );
+    for (i, guess) in trace.guesses.iter().enumerate() {
+        println!(
+            "  {} {}",
+            format!("{}.", i + 1).black(),
+            calculate_guess_result(answer, guess).paint(palette)
+        );
+    }
+    if let Some(path) = md_path {
+        // replay the solve to recover what it saw at each step
+        let mut transcript = MarkdownTranscript::new(path, palette);
+        let mut known_info = vec![];
+        let mut possible_words = words.to_vec();
+        for (i, guess) in trace.guesses.iter().enumerate() {
+            known_info.push(calculate_guess_result(answer, guess));
+            possible_words = ranking.rank(
+                filter_using_known_info(&possible_words, &known_info),
+                &known_info,
+            );
+            let reasoning = match trace.guesses.get(i + 1) {
+                _ if guess == answer => "Solved!".to_string(),
+                Some(next) if *next != possible_words[0].word => format!(
+                    "anagrams can't be told apart by score, so suggested {} instead",
+                    next
+                ),
+                _ => describe_choice(&possible_words, &known_info, false),
+            };
+            transcript.record(
+                guess,
+                known_info.last().unwrap(),
+                possible_words.len(),
+                reasoning,
+            )?;
+        }
+    }
+    if trace.solved {
+        println!(
+            "Solved {} in {} guesses",
+            answer.blue(),
+            trace.guesses.len()
+        );
+    } else {
+        println!(
+            "{} isn't solvable within {} guesses",
+            answer.blue(),
+            max_guesses
+        );
+    }
+    Ok(())
+}
+
+/// Prints each guess the solver makes against `answer` from `openers` with
+/// its clue spelt out, one per line so scripts can read them, exiting with
+/// `EXIT_UNSOLVED` if the answer isn't found
+fn print_solve(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    openers: &[&str],
+    answer: &String,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+) -> Result<(), SolverError> {
+    if !words.iter().any(|sw| sw.word == *answer) {
+        return Err(SolverError::NotInWordList(answer.clone()));
+    }
+
+    let trace = solve_word(
+        words,
+        guesses,
+        openers,
+        answer,
+        ranking,
+        hard,
+        max_guesses,
+        None,
+    );
+    for guess in &trace.guesses {
+        println!(
+            "{} {}",
+            guess,
+            calculate_guess_result(answer, guess).pattern()
+        );
+    }
+    if !trace.solved {
+        eprintln!(
+            "{}",
+            format!("Didn't find {} within {} guesses", answer, max_guesses).red()
+        );
+        std::process::exit(EXIT_UNSOLVED);
+    }
+    Ok(())
+}
+
+/// Plays the solver against `answer` the same way as `print_solve`, but after
+/// each guess says why it was played, how much its clue narrowed the words
+/// down, and the three best candidates it passed over
+#[allow(clippy::too_many_arguments)]
+fn print_explained_solve(
+    words: &[ScoredWord],
+    guesses: &[ScoredWord],
+    openers: &[&str],
+    answer: &String,
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    palette: Palette,
+) -> Result<(), SolverError> {
+    if !words.iter().any(|sw| sw.word == *answer) {
+        return Err(SolverError::NotInWordList(answer.clone()));
+    }
+
+    let trace = solve_word(
+        words,
+        guesses,
+        openers,
+        answer,
+        ranking,
+        hard,
+        max_guesses,
+        None,
+    );
+    let mut known_info = vec![];
+    let mut candidates = ranking.rank(words.to_vec(), &known_info);
+    for (i, guess) in trace.guesses.iter().enumerate() {
+        let total_score = candidates.iter().map(|sw| sw.score).sum::<usize>();
+        let reason = match candidates.first() {
+            _ if openers.get(i) == Some(&guess.as_str()) => "the opener".to_string(),
+            Some(sw) if sw.word == *guess => format!(
+                "the likeliest candidate at {}%",
+                (100.0 * sw.score as f64 / total_score as f64).smooth_str()
+            ),
+            _ => "a probe to tell apart words made of the same letters".to_string(),
+        };
+        println!(
+            "\n{} {}, {}",
+            format!("{}.", i + 1).black(),
+            guess.blue(),
+            reason
+        );
+        println!(
+            "   Picked from {} {}, expecting {} bits from its clue",
+            candidates.len(),
+            if candidates.len() == 1 {
+                "word"
+            } else {
+                "words"
+            },
+            partition_entropy(&partition(guess, &candidates)).smooth_str()
+        );
+        let passed_over: Vec<String> = candidates
+            .iter()
+            .filter(|sw| sw.word != *guess)
+            .take(3)
+            .map(|sw| {
+                format!(
+                    "{} ({})",
+                    sw.word,
+                    describe_suggestion(sw, &candidates, total_score, false)
+                )
+            })
+            .collect();
+        if !passed_over.is_empty() {
+            println!("   Passed over {}", passed_over.join(", "));
+        }
+
+        let clue = calculate_guess_result(answer, guess);
+        if guess == answer {
+            println!("   {} solved it", clue.paint(palette));
+            break;
+        }
+        known_info.push(clue);
+        let before = candidates.len();
+        candidates = ranking.rank(
+            filter_using_known_info(&candidates, newest_clue(&known_info)),
+            &known_info,
+        );
+        println!(
+            "   {} left {} {}, {} bits learnt",
+            known_info.last().unwrap().paint(palette),
+            candidates.len(),
+            if candidates.len() == 1 {
+                "word"
+            } else {
+                "words"
+            },
+            (before as f64 / candidates.len().max(1) as f64)
+                .log2()
+                .smooth_str()
+        );
+    }
+
+    if trace.solved {
+        println!(
+            "\nSolved {} in {} guesses",
+            answer.blue(),
+            trace.guesses.len()
+        );
+    } else {
+        eprintln!(
+            "{}",
+            format!("Didn't find {} within {} guesses", answer, max_guesses).red()
+        );
+        std::process::exit(EXIT_UNSOLVED);
+    }
+    Ok(())
+}
+
+/// Writes diagnostics, to stderr unless a test gives it somewhere else,
+/// coloured by level, so they stay out of the way of the suggestions on stdout.
+/// The spinner is cleared first so it doesn't draw over them
+struct Logger {
+    status: &'static Status,
+    out: std::sync::Mutex<Box<dyn Write + Send>>,
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.write(record);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl Logger {
+    fn write(&self, record: &log::Record) {
+        let message = record.args().to_string();
+        let message = match record.level() {
+            log::Level::Error => message.red(),
+            log::Level::Warn => message.yellow(),
+            _ => message.black(),
+        };
+        self.status.suspend(|| {
+            // a record that can't be written is lost, rather than stopping the solver
+            let _ = writeln!(self.out.lock().unwrap(), "{}", message);
+        });
+    }
+}
+
+/// Shows warnings and info by default, then debug and trace records for each
+/// `-v` given. `quiet` leaves only warnings and errors
+fn init_logging(verbose: u8, quiet: bool) {
+    let logger = Logger {
+        status: &STATUS,
+        out: std::sync::Mutex::new(Box::new(std::io::stderr())),
+    };
+    log::set_logger(Box::leak(Box::new(logger))).expect("Could not set up logging");
+    log::set_max_level(log_level(verbose, quiet));
+}
+
+/// The most detailed level of record shown for the given `-v` count
+fn log_level(verbose: u8, quiet: bool) -> log::LevelFilter {
+    match verbose {
+        _ if quiet => log::LevelFilter::Warn,
+        0 => log::LevelFilter::Info,
+        1 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    }
+}
+
+/// The spinner on the terminal, shared by `Progress` and the logger so that
+/// anything either of them writes clears it first
+static STATUS: Status = Status(std::sync::Mutex::new(None));
+
+/// The text of the work in progress, if there is any, and its spinner while
+/// it's drawn
+struct Status(std::sync::Mutex<Option<(String, Option<Spinner>)>>);
+
+impl Status {
+    /// Clears the spinner while `f` runs, so it can print or prompt without the
+    /// spinner drawing over it, then starts the spinner again. The lock isn't
+    /// held while `f` runs, so it can log
+    fn suspend<T>(&self, f: impl FnOnce() -> T) -> T {
+        if let Some((_, spinner)) = self.0.lock().unwrap().as_mut() {
+            if let Some(spinner) = spinner.take() {
+                spinner.clear();
+            }
+        }
+        let result = f();
+        if let Some((text, spinner)) = self.0.lock().unwrap().as_mut() {
+            if spinner.is_none() {
+                *spinner = Progress::spin(text);
+            }
+        }
+        result
+    }
+}
+
+/// A spinner that has to be stopped for anything else to be written to the
+/// terminal, otherwise its redraws garble the output
+struct Progress {
+    status: &'static Status,
+}
+
+impl Progress {
+    /// Starts the spinner, unless colours are off, as they are when stdout isn't
+    /// a terminal for it to redraw on, or `--quiet` was given
+    fn start(text: &str) -> Self {
+        Progress::start_on(&STATUS, text)
+    }
+
+    fn start_on(status: &'static Status, text: &str) -> Self {
+        *status.0.lock().unwrap() = Some((text.to_string(), Progress::spin(text)));
+        Progress { status }
+    }
+
+    fn spin(text: &str) -> Option<Spinner> {
+        (colored::control::SHOULD_COLORIZE.should_colorize()
+            && SPINNERS.load(Ordering::Relaxed)
+            && !QUIET.load(Ordering::Relaxed))
+        .then(|| Spinner::new(spinners::Aesthetic, text.to_string(), None))
+    }
+
+    fn update_text(&mut self, text: String) {
+        if let Some((current, spinner)) = self.status.0.lock().unwrap().as_mut() {
+            if let Some(spinner) = spinner {
+                spinner.update_text(text.clone());
+            }
+            *current = text;
+        }
+    }
+
+    /// Clears the spinner while `f` runs, so it can print or prompt without the
+    /// spinner drawing over it, then starts the spinner again
+    fn suspend<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        self.status.suspend(f)
+    }
+
+    /// Prints how the work is going with `f`, clearing the spinner first. With
+    /// `--quiet` nothing is printed, so only the final message is left
+    fn detail(&mut self, f: impl FnOnce()) {
+        if !QUIET.load(Ordering::Relaxed) {
+            self.suspend(f);
+        }
+    }
+
+    /// Stops the spinner, replacing it with a final message, which is printed
+    /// even with `--quiet`. Without a spinner
+    /// the message goes to stderr, keeping stdout for a mode's output
+    fn info(self, message: &str) {
+        let running = self.status.0.lock().unwrap().take();
+        match running {
+            Some((_, Some(spinner))) => spinner.info(message),
+            _ => eprintln!("{}", message),
+        }
+    }
+}
+
+impl Drop for Progress {
+    /// Stops the spinner if it wasn't replaced by a message
+    fn drop(&mut self) {
+        if let Some((_, Some(spinner))) = self.status.0.lock().unwrap().take() {
+            spinner.clear();
+        }
+    }
+}
+
+/// Chooses the optimal strategy for the given word list. If the deadline passes
+/// before every strategy has been tested, the best of those that finished wins.
+/// Returns the winner's histogram too, unless nothing finished in time, and
+/// whether every strategy was tested.
+#[allow(clippy::too_many_arguments)]
+fn choose_optimal_strategy(
+    words: &Vec<ScoredWord>,
+    guesses: &[ScoredWord],
+    ranking: &Ranking,
+    hard: bool,
+    max_guesses: usize,
+    deadline: Option<Instant>,
+    rng: &mut StdRng,
+    frequencies: &[[usize; 26]; 5],
+    patterns: &Patterns,
+) -> (Strategy, String, Option<GuessHistogram>, bool) {
+    let mut sp = Progress::start("Choosing optimal strategy for this word list");
+
+    let start = std::time::Instant::now();
+
+    let options = STRATEGIES;
+
+    // the strategies are tested at the same time, so each gets its own rng, seeded
+    // in order from the one given, to pick the same openers whichever finishes
+    // first
+    let seeds: Vec<u64> = options.iter().map(|_| rng.gen()).collect();
+    let finished = AtomicUsize::new(0);
+    let tested: Vec<Option<(GuessHistogram, String)>> = std::thread::scope(|scope| {
+        let testing = scope.spawn(|| {
+            options
+                .par_iter()
+                .zip(&seeds)
+                .map(|(s, seed)| {
+                    let mut rng = StdRng::seed_from_u64(*seed);
+                    let result = test_strategy(
+                        words,
+                        guesses,
+                        *s,
+                        ranking,
+                        hard,
+                        max_guesses,
+                        deadline,
+                        &mut rng,
+                        frequencies,
+                        patterns,
+                    );
+                    finished.fetch_add(1, Ordering::Relaxed);
+                    result
+                })
+                .collect()
+        });
+        while !testing.is_finished() {
+            sp.update_text(format!(
+                "{} Testing strategies",
+                format!("[{}/{}]", finished.load(Ordering::Relaxed), options.len()).black()
+            ));
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        testing.join().expect("Testing strategies panicked")
+    });
+
+    // reported in the order they're listed, however they finished
+    let results: Vec<(Strategy, (GuessHistogram, String))> = options
+        .iter()
+        .zip(tested)
+        .filter_map(|(s, result)| Some((*s, result?)))
+        .collect();
+    sp.detail(|| {
+        for (i, (s, result)) in results.iter().enumerate() {
+            eprintln!(
+                "  {} {} solves {}/{} words within {} guesses, averaging {}",
+                format!("[{}/{}]", i + 1, options.len()).black(),
+                format!("{:?}", s).magenta(),
+                result.0.solved_count(),
+                words.len(),
+                max_guesses,
+                result.0.average_guesses().smooth_str()
+            );
+        }
+    });
+
+    let time_limited = results.len() < options.len();
+    if results.is_empty() {
+        // nothing finished in time, so fall back on the first strategy without
+        // knowing how well it does
+        let fallback = options[0];
+        sp.info(&format!(
+            "{} Ran out of time testing strategies, using {} untested",
+            format!("[{:?}]", start.elapsed()).black(),
+            format!("{:?}", fallback).magenta()
+        ));
+        return (
+            fallback,
+            get_first_guess_using(words, guesses, fallback, frequencies, rng),
+            None,
+            false,
+        );
+    }
 
-    options
+    // solving the most words matters most, but between strategies that solve as
+    // many, the one that takes fewer guesses is better. the first in `STRATEGIES`
+    // wins any exact tie
+    let winner = results
         .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            sp.update_text(format!(
-                "{} Testing {} strategy",
-                format!("[{}/{}]", i + 1, options.len()).black(),
-                format!("{:?}", s).magenta()
-            ));
-            (s.clone(), test_strategy(words, s.clone()))
+        .reduce(|best, candidate| {
+            let (best_histogram, candidate_histogram) = (&best.1 .0, &candidate.1 .0);
+            let better = candidate_histogram
+                .solved_count()
+                .cmp(&best_histogram.solved_count())
+                .then(
+                    best_histogram
+                        .average_guesses()
+                        .total_cmp(&candidate_histogram.average_guesses()),
+                );
+            if better.is_gt() {
+                candidate
+            } else {
+                best
+            }
         })
-        .collect::<Vec<(Strategy, (i32, String))>>()
-        .iter()
-        .for_each(|(s, (count, guess))| {
-            results.insert(s.clone(), (*count, guess.clone()));
-        });
+        .unwrap();
 
-    let winner = results.iter().max_by_key(|(_, (count, _))| *count).unwrap();
+    sp.detail(|| winner.1 .0.print());
 
-    let total_words = words.len() * options.len();
+    let total_words = words.len() * results.len();
 
+    let solvable = winner.1 .0.solved_count();
     sp.info(&format!(
-        "{} Optimal strategy is {} with {}/{} solvable words ({}%)\n  {}",
+        "{} Optimal strategy is {} with {}/{} words solvable within {} guesses ({}%), averaging {}{}\n  {}",
         format!("[{:?}]", start.elapsed()).black(),
         format!("{:?}", winner.0).magenta(),
-        winner.1 .0,
+        solvable,
         words.len(),
-        (100.0 * (winner.1 .0 as f64) / (words.len() as f64)).smooth_str(),
+        max_guesses,
+        (100.0 * (solvable as f64) / (words.len() as f64)).smooth_str(),
+        winner.1 .0.average_guesses().smooth_str(),
+        if time_limited {
+            format!(
+                " (ran out of time after {}/{} strategies)",
+                results.len(),
+                options.len()
+            )
+            .yellow()
+            .to_string()
+        } else {
+            String::new()
+        },
         format!(
             "Solved {} words using {} different strategies ({} wps)",
             total_words,
-            options.len(),
+            results.len(),
             (total_words as f64 / start.elapsed().as_secs_f64()).smooth_str()
         )
         .black()
     ));
+    (
+        winner.0.clone(),
+        winner.1 .1.clone(),
+        Some(winner.1 .0.clone()),
+        !time_limited,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solver::{
+        anagram_probe, count_distinct_patterns, favour_common_bigrams, get_first_guess,
+        optimise_results, penalise_repeats, scored_words, worst_case,
+    };
+
+    /// How many words of the bundled list the slower tests use, so they're
+    /// quick enough to run on every change
+    const SAMPLE_SIZE: usize = 500;
+
+    /// The share of a sample the chosen strategy has to solve within
+    /// `MAX_GUESSES`. It's higher than the solver manages on the whole list,
+    /// since a sample has fewer words alike enough to be hard to tell apart
+    const SOLVE_RATE_THRESHOLD: f64 = 0.95;
+
+    /// Words from the bundled list, picked the same way every run
+    fn sample_of_bundled_list(count: usize) -> Vec<ScoredWord> {
+        read_word_list(WORDS)
+            .choose_multiple(&mut StdRng::seed_from_u64(0), count)
+            .cloned()
+            .collect()
+    }
+
+    fn patterns_for(words: &[ScoredWord]) -> Patterns {
+        Patterns::new(PatternMatrix::build(b"test", words, words), words, words)
+    }
+
+    /// A path in the temp directory that's only this test run's, removed when
+    /// it's dropped, even if the test fails first
+    struct ScratchFile(std::path::PathBuf);
+
+    impl ScratchFile {
+        fn new(name: &str) -> Self {
+            ScratchFile(std::env::temp_dir().join(format!(
+                "solver-{}-{}",
+                std::process::id(),
+                name
+            )))
+        }
+
+        fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for ScratchFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn chosen_strategy_solves_most_of_the_bundled_list() {
+        let words = sample_of_bundled_list(SAMPLE_SIZE);
+        let (strategy, opener, histogram, complete) = choose_optimal_strategy(
+            &words,
+            &words,
+            &Ranking::default(),
+            false,
+            MAX_GUESSES,
+            None,
+            &mut StdRng::seed_from_u64(0),
+            &positional_frequencies(&words),
+            &patterns_for(&words),
+        );
+        assert!(complete, "no deadline was set, so every strategy finishes");
+        let histogram = histogram.expect("no deadline was set, so every strategy finishes");
+        let rate = histogram.solved_count() as f64 / words.len() as f64;
+        assert!(
+            rate >= SOLVE_RATE_THRESHOLD,
+            "{:?} opening with {} only solved {:.1}% of the sample",
+            strategy,
+            opener,
+            100.0 * rate
+        );
+    }
+
+    #[test]
+    fn the_answer_is_never_filtered_out() {
+        let words = read_word_list(WORDS);
+        let mut rng = StdRng::seed_from_u64(1);
+        for answer in sample_of_bundled_list(50) {
+            let mut known_info = vec![];
+            let mut candidates = words.clone();
+            for guess in words.choose_multiple(&mut rng, MAX_GUESSES - 1) {
+                known_info.push(calculate_guess_result(&answer.word, &guess.word));
+                candidates = optimise_results(
+                    filter_using_known_info(&candidates, &known_info),
+                    &known_info,
+                );
+                assert!(
+                    candidates.iter().any(|sw| sw.word == answer.word),
+                    "{} was ruled out by {:?}",
+                    answer.word,
+                    known_info
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn filtering_by_the_newest_clue_matches_filtering_by_all_of_them() {
+        let words = read_word_list(WORDS);
+        let mut rng = StdRng::seed_from_u64(2);
+        for answer in sample_of_bundled_list(50) {
+            let mut known_info = vec![];
+            let mut candidates = words.clone();
+            for guess in words.choose_multiple(&mut rng, MAX_GUESSES - 1) {
+                known_info.push(calculate_guess_result(&answer.word, &guess.word));
+                candidates = filter_using_known_info(&candidates, newest_clue(&known_info));
+                let incremental: HashSet<&str> =
+                    candidates.iter().map(|sw| sw.word.as_str()).collect();
+                let full = filter_using_known_info(&words, &known_info);
+                let full: HashSet<&str> = full.iter().map(|sw| sw.word.as_str()).collect();
+                assert_eq!(incremental, full, "after {:?}", known_info);
+            }
+        }
+    }
+
+    #[test]
+    fn common_bigrams_only_change_the_order() {
+        let words = read_word_list(WORDS);
+        // the clues from the example in `optimise_results`, which leave groan,
+        // green and grown
+        let known_info = vec![
+            GuessResult::from_pattern("drunk", "-G-Y-").unwrap(),
+            GuessResult::from_pattern("fight", "--Y--").unwrap(),
+        ];
+        let ranked = optimise_results(filter_using_known_info(&words, &known_info), &known_info);
+        // they all score the same, so they start in reverse alphabetical order
+        // to show the letter pairs alone decide it
+        let mut reordered: Vec<ScoredWord> = ranked.iter().rev().cloned().collect();
+        favour_common_bigrams(&mut reordered, &known_info, &bigram_frequencies(&words));
+
+        // the same words with the same scores, whatever their order
+        let sorted = |words: &[ScoredWord]| {
+            let mut words: Vec<(String, usize)> =
+                words.iter().map(|sw| (sw.word.clone(), sw.score)).collect();
+            words.sort();
+            words
+        };
+        assert_eq!(sorted(&ranked), sorted(&reordered));
+        let position = |word: &str| reordered.iter().position(|sw| sw.word == word);
+        assert!(position("groan") < position("grown"));
+    }
+
+    #[test]
+    fn duplicate_words_are_only_counted_once() {
+        let mut words = read_word_list("slate 3\ncrane\nslate 5\ntrace\ncrane\n");
+        assert_eq!(normalise_word_list(&mut words), 2);
+        let normalised: Vec<(&str, usize)> = words
+            .iter()
+            .map(|sw| (sw.word.as_str(), sw.score))
+            .collect();
+        assert_eq!(normalised, [("crane", 1), ("slate", 5), ("trace", 1)]);
+
+        // the order they came in makes no difference
+        let mut reversed = read_word_list("crane\ntrace\nslate 5\ncrane\nslate 3\n");
+        normalise_word_list(&mut reversed);
+        let reversed: Vec<(&str, usize)> = reversed
+            .iter()
+            .map(|sw| (sw.word.as_str(), sw.score))
+            .collect();
+        assert_eq!(normalised, reversed);
+    }
+
+    #[test]
+    fn choosing_a_strategy_is_reproducible() {
+        let words = scored_words(&[
+            "crane", "crate", "slate", "plate", "irate", "trace", "grate", "skate",
+        ]);
+        let patterns = patterns_for(&words);
+        let choose = || {
+            choose_optimal_strategy(
+                &words,
+                &words,
+                &Ranking::default(),
+                false,
+                MAX_GUESSES,
+                None,
+                &mut StdRng::seed_from_u64(7),
+                &positional_frequencies(&words),
+                &patterns,
+            )
+        };
+        assert_eq!(choose(), choose());
+    }
+
+    #[test]
+    fn yellows_in_different_places_force_a_unique_placement() {
+        // a is yellow first in one clue and second in another, and the last two
+        // letters are green, so the only place left for it is the middle
+        let known_info = vec![
+            GuessResult::from_pattern("abbbb", "Y----").unwrap(),
+            GuessResult::from_pattern("daddd", "-Y---").unwrap(),
+            GuessResult::from_pattern("eeesm", "---GG").unwrap(),
+        ];
+        let words = scored_words(&["chasm", "achsm", "cahsm", "prism"]);
+        let remaining = filter_using_known_info(&words, &known_info);
+        let remaining: Vec<&str> = remaining.iter().map(|sw| sw.word.as_str()).collect();
+        assert_eq!(remaining, ["chasm"]);
+        // with a forced into the middle, no position is still open to score
+        assert!(scored_positions(&known_info).is_empty());
+    }
+
+    #[test]
+    fn guessing_the_answer_first_is_a_one_guess_solve() {
+        let words = scored_words(&["crane", "crate", "slate"]);
+        let trace = solve_word(
+            &words,
+            &words,
+            &["crane"],
+            &"crane".to_string(),
+            &Ranking::default(),
+            false,
+            MAX_GUESSES,
+            None,
+        );
+        assert!(trace.solved);
+        assert_eq!(trace.guesses, ["crane"]);
+    }
+
+    #[test]
+    fn a_passed_deadline_still_gives_a_usable_opener() {
+        let words = scored_words(&["crane", "crate", "slate", "plate", "irate", "trace"]);
+        let (strategy, opener, histogram, complete) = choose_optimal_strategy(
+            &words,
+            &words,
+            &Ranking::default(),
+            false,
+            MAX_GUESSES,
+            Some(Instant::now()),
+            &mut StdRng::seed_from_u64(0),
+            &positional_frequencies(&words),
+            &patterns_for(&words),
+        );
+        // nothing was tested, so it's partial and mustn't be cached as the best
+        assert!(!complete);
+        assert!(histogram.is_none());
+        assert_eq!(strategy, STRATEGIES[0]);
+        assert_eq!(opener.len(), 5);
+        assert!(opener.chars().all(|c| c.is_ascii_lowercase()));
+    }
+
+    #[test]
+    fn a_custom_colour_mapping_reads_like_the_default() {
+        let letters = parse_colors("XOZ").unwrap();
+        assert_eq!(
+            parse_pattern_with("crane", "zxozz", letters).unwrap(),
+            parse_pattern("crane", "bgybb").unwrap()
+        );
+        assert_eq!(
+            parse_pattern_with("crane", "Z*ZOX", letters).unwrap(),
+            parse_pattern("crane", "b*byg").unwrap()
+        );
+        // every colour needs its own letter
+        assert!(parse_colors("ggb").is_err());
+        assert!(parse_colors("gy").is_err());
+        assert!(parse_colors("gy*").is_err());
+    }
+
+    #[test]
+    fn words_of_other_lengths_are_skipped() {
+        let contents = "crane\nslates\nox\nplate 2\n";
+        assert!(validate_word_list(contents).is_ok());
+        let words: Vec<String> = read_word_list(contents)
+            .into_iter()
+            .map(|sw| sw.word)
+            .collect();
+        assert_eq!(words, ["crane", "plate"]);
+        // skipping every word would leave nothing to solve
+        assert!(validate_word_list("slates\nox\n").is_err());
+    }
+
+    #[test]
+    fn repeated_letters_are_coloured_like_the_nyt_game() {
+        // (answer, guess, clue), checked against the NYT game
+        let cases = [
+            ("abide", "speed", "--Y-Y"),
+            ("speed", "abide", "---YY"),
+            ("three", "eerie", "Y-G-G"),
+            ("eerie", "three", "--GYG"),
+            ("kebab", "geese", "-G---"),
+            ("geese", "kebab", "-G---"),
+            ("geese", "eerie", "YG--G"),
+            ("abbey", "babka", "YYG--"),
+        ];
+        for (answer, guess, clue) in cases {
+            assert_eq!(
+                calculate_guess_result(&answer.to_string(), &guess.to_string()).pattern(),
+                clue,
+                "{} against {}",
+                guess,
+                answer
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_letter_clues_bound_the_count() {
+        // speed against abide has one e yellow and one red, so the answer has
+        // exactly one e
+        let clue = calculate_guess_result(&"abide".to_string(), &"speed".to_string());
+        let bounds = letter_bounds(&clue);
+        assert_eq!(bounds[&'e'], (1, Some(1)));
+        assert_eq!(bounds[&'d'], (1, None));
+
+        // the words a clue keeps are exactly those that would have given it
+        let words = read_word_list(WORDS);
+        for (answer, guess) in [
+            ("abide", "speed"),
+            ("three", "eerie"),
+            ("geese", "eerie"),
+            ("abbey", "babka"),
+            ("kebab", "geese"),
+        ] {
+            let clue = calculate_guess_result(&answer.to_string(), &guess.to_string());
+            let kept: HashSet<String> =
+                filter_using_known_info(&words, std::slice::from_ref(&clue))
+                    .into_iter()
+                    .map(|sw| sw.word)
+                    .collect();
+            assert!(kept.contains(answer), "{} was ruled out", answer);
+            for sw in &words {
+                let gives_clue = calculate_guess_result(&sw.word, &guess.to_string()) == clue;
+                assert_eq!(
+                    kept.contains(&sw.word),
+                    gives_clue,
+                    "{} against {} for {}",
+                    guess,
+                    sw.word,
+                    answer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn minimax_opener_has_a_smaller_worst_case_than_frequency() {
+        let words = read_word_list(WORDS);
+        let worst = |strategy| {
+            let opener = get_first_guess(&words, strategy, &mut StdRng::seed_from_u64(0));
+            worst_case(&partition(&opener, &words))
+        };
+        let minimax = worst(Strategy::Minimax);
+        let frequency = worst(Strategy::FrequencyPositionAware);
+        assert!(
+            minimax < frequency,
+            "minimax's opener can leave {} words, frequency's {}",
+            minimax,
+            frequency
+        );
+    }
+
+    #[test]
+    fn generated_fixtures_replay_to_their_expected_suggestions() {
+        let words = read_word_list(WORDS);
+        let ranking = Ranking::default();
+        for fixture in gen_fixtures(&words, "slate", &ranking, 0, 20, MAX_GUESSES) {
+            // replayed the way a session takes clues, from the written patterns
+            let mut known_info = vec![];
+            let mut candidates = ranking.rank(words.clone(), &known_info);
+            for step in &fixture.steps {
+                known_info.push(parse_pattern(&step.guess, &step.pattern).unwrap());
+                candidates = ranking.rank(
+                    filter_using_known_info(&candidates, newest_clue(&known_info)),
+                    &known_info,
+                );
+                let suggestion =
+                    next_guess(&candidates, &words, &["slate"], known_info.len(), false);
+                assert_eq!(
+                    suggestion, step.expected_next,
+                    "replaying {} after {:?}",
+                    fixture.answer, known_info
+                );
+            }
+        }
+    }
+
+    /// Counts the records logged with the `verbosity_test` target, so records
+    /// from tests running alongside don't get in the way
+    struct CountingLogger(AtomicUsize);
+
+    impl log::Log for CountingLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::max_level()
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) && record.target() == "verbosity_test" {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn each_verbose_flag_shows_more_records() {
+        static LOGGER: CountingLogger = CountingLogger(AtomicUsize::new(0));
+        log::set_logger(&LOGGER).expect("no other test sets a logger");
+        let shown = |verbose, quiet| {
+            log::set_max_level(log_level(verbose, quiet));
+            LOGGER.0.store(0, Ordering::Relaxed);
+            for level in log::Level::iter() {
+                log::log!(target: "verbosity_test", level, "a {} record", level);
+            }
+            LOGGER.0.load(Ordering::Relaxed)
+        };
+        assert_eq!(shown(0, true), 2, "--quiet leaves errors and warnings");
+        assert_eq!(shown(0, false), 3);
+        assert_eq!(shown(1, false), 4, "-v adds debug records");
+        assert_eq!(shown(2, false), 5, "-vv adds trace records");
+        assert_eq!(shown(2, true), 2);
+    }
+
+    #[test]
+    fn cheat_sheet_matches_the_solvers_second_guess() {
+        let words = read_word_list(WORDS);
+        let opener = "slate".to_string();
+        for (clue, _, second_guess) in cheat_sheet(&words, &opener, &Ranking::default(), 20) {
+            let answer = words
+                .iter()
+                .find(|sw| calculate_guess_result(&sw.word, &opener) == clue)
+                .expect("every clue on the sheet comes from a word on the list");
+            let trace = solve_word(
+                &words,
+                &words,
+                &["slate"],
+                &answer.word,
+                &Ranking::default(),
+                false,
+                MAX_GUESSES,
+                None,
+            );
+            assert_eq!(trace.guesses[1], second_guess, "after {:?}", clue);
+        }
+    }
+
+    #[test]
+    fn an_anagram_cluster_gets_a_probe_that_tells_it_apart() {
+        let all_words = read_word_list(WORDS);
+        // guessing musca can't tell caums from camus, so a probe is worth more
+        let words = scored_words(&["musca", "camus", "caums", "sumac"]);
+        let (cluster, probe) =
+            anagram_probe(&words, &all_words).expect("the whole set is one cluster");
+        let mut sorted = cluster.clone();
+        sorted.sort();
+        assert_eq!(sorted, ["camus", "caums", "musca", "sumac"]);
+        assert!(
+            count_distinct_patterns(&probe, &cluster)
+                > count_distinct_patterns(&"musca".to_string(), &cluster),
+            "{} doesn't split the cluster any better than musca",
+            probe
+        );
+        assert_eq!(next_guess(&words, &all_words, &["slate"], 1, false), probe);
+    }
+
+    #[test]
+    fn words_fill_columns_top_to_bottom_within_the_width() {
+        // seven 5 letter cells are 7 wide with the gap, so two columns fit in 20
+        assert_eq!(
+            column_layout(&[5; 7], 20),
+            [vec![0, 4], vec![1, 5], vec![2, 6], vec![3]]
+        );
+        // a cell wider than the terminal still gets a column to itself
+        assert_eq!(column_layout(&[30, 5], 20), [vec![0], vec![1]]);
+        assert_eq!(column_layout(&[5; 3], 80), [vec![0, 1, 2]]);
+        assert!(column_layout(&[], 80).is_empty());
+    }
+
+    #[test]
+    fn a_wildcard_tile_doesnt_constrain_its_position() {
+        let words = read_word_list(WORDS);
+        let wild = parse_pattern("crane", "*****").unwrap();
+        assert_eq!(filter_using_known_info(&words, &[wild]).len(), words.len());
+
+        // only the green tiles count, so any first letter goes
+        let clue = parse_pattern("crane", "*gggg").unwrap();
+        let mut kept: Vec<String> = filter_using_known_info(&words, &[clue])
+            .into_iter()
+            .map(|sw| sw.word)
+            .collect();
+        kept.sort();
+        let mut expected: Vec<String> = words
+            .iter()
+            .filter(|sw| sw.word.ends_with("rane"))
+            .map(|sw| sw.word.clone())
+            .collect();
+        expected.sort();
+        assert!(expected.len() > 1);
+        assert_eq!(kept, expected);
+    }
+
+    #[test]
+    fn each_rule_of_a_clue_accounts_for_its_share_of_the_words_removed() {
+        let words = read_word_list(WORDS);
+        // one e is yellow and the other red, so there's exactly one
+        let clue = calculate_guess_result(&"abide".to_string(), &"speed".to_string());
+        let explanation = explain_filter(&words, &clue);
+        let rules: Vec<&str> = explanation.iter().map(|(rule, _)| rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            [
+                "red 's'",
+                "red 'p'",
+                "yellow 'e'@3",
+                "red 'e'",
+                "yellow 'd'@5",
+                "exactly 1 'e'"
+            ]
+        );
+        let removed: usize = explanation.iter().map(|(_, count)| count).sum();
+        let left = filter_using_known_info(&words, std::slice::from_ref(&clue)).len();
+        assert_eq!(removed, words.len() - left);
+    }
+
+    #[test]
+    fn tie_shuffles_repeat_for_a_seed_and_vary_between_seeds() {
+        let words = read_word_list(WORDS);
+        let known_info = [parse_pattern("crane", "bbbbb").unwrap()];
+        let candidates = filter_using_known_info(&words, &known_info);
+        let shuffled = |seed| {
+            let ranking = Ranking {
+                tie_seed: Some(seed),
+                ..Ranking::default()
+            };
+            ranking
+                .rank(candidates.clone(), &known_info)
+                .into_iter()
+                .map(|sw| (sw.word, sw.score))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shuffled(1), shuffled(1));
+        assert_ne!(shuffled(1), shuffled(2));
+        // runs of ties mustn't mix
+        assert!(shuffled(1).windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn tie_shuffles_keep_the_entropy_order() {
+        let words = read_word_list(WORDS);
+        let known_info = [parse_pattern("crane", "bbgbg").unwrap()];
+        let candidates = filter_using_known_info(&words, &known_info);
+        let ranked = |tie_seed| {
+            let ranking = Ranking {
+                scorer: Scorer::Entropy,
+                tie_seed,
+                ..Ranking::default()
+            };
+            ranking.rank(candidates.clone(), &known_info)
+        };
+        let keys = |ranked: &[ScoredWord]| {
+            ranked
+                .iter()
+                .map(|sw| {
+                    let remaining = expected_remaining(&partition(&sw.word, &candidates));
+                    (remaining.to_bits(), sw.score)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let unshuffled = ranked(None);
+        let shuffled = ranked(Some(1));
+        // only words that split the rest as well and are as likely swap places
+        assert_eq!(keys(&shuffled), keys(&unshuffled));
+        let order =
+            |ranked: &[ScoredWord]| ranked.iter().map(|sw| sw.word.clone()).collect::<Vec<_>>();
+        assert_ne!(order(&shuffled), order(&unshuffled));
+    }
+
+    #[test]
+    fn answer_frequencies_shift_the_commonest_letter_in_each_position() {
+        let commonest = |words: &[ScoredWord]| {
+            positional_frequencies(words).map(|counts| {
+                let most = (0..26).max_by_key(|&i| (counts[i], std::cmp::Reverse(i)));
+                (b'a' + most.unwrap() as u8) as char
+            })
+        };
+        let mut words = scored_words(&["crane", "brine", "brave", "slate"]);
+        assert_eq!(commonest(&words), ['b', 'r', 'a', 'n', 'e']);
+
+        // crane is by far the likeliest answer, so its letters win out
+        apply_answer_frequencies(&mut words, "crane 10\nslate 2\n");
+        assert_eq!(commonest(&words), ['c', 'r', 'a', 'n', 'e']);
+        assert_eq!(
+            words.iter().map(|sw| sw.score).collect::<Vec<_>>(),
+            [10, 1, 1, 2]
+        );
+    }
+
+    #[test]
+    fn only_keeps_suggestions_to_words_in_both_lists() {
+        let words = read_word_list(WORDS);
+        // zzzzz isn't on the main list, so it's dropped with a warning
+        let only = intersect_words(words, "crane slate trace grace zzzzz");
+        let mut kept: Vec<&str> = only.iter().map(|sw| sw.word.as_str()).collect();
+        kept.sort();
+        assert_eq!(kept, ["crane", "grace", "slate", "trace"]);
+
+        let clue = parse_pattern("crane", "yggbg").unwrap();
+        let known_info = [clue];
+        let suggestions =
+            optimise_results(filter_using_known_info(&only, &known_info), &known_info);
+        let mut suggested: Vec<&str> = suggestions.iter().map(|sw| sw.word.as_str()).collect();
+        suggested.sort();
+        assert_eq!(suggested, ["grace", "trace"]);
+    }
+
+    #[test]
+    fn opener_transitions_match_filtering_by_the_clue() {
+        let words = read_word_list(WORDS);
+        let opener = "slate".to_string();
+        let transitions = OpenerTransitions::build(&opener, &words);
+        let names =
+            |words: Vec<ScoredWord>| words.into_iter().map(|sw| sw.word).collect::<Vec<_>>();
+        for code in 0..243 {
+            let known_info = [GuessResult::from_code(code, &opener)];
+            let looked_up = transitions
+                .lookup(&known_info, &words)
+                .expect("every clue for the opener is in the table");
+            assert_eq!(
+                names(looked_up),
+                names(filter_using_known_info(&words, &known_info)),
+                "after {:?}",
+                known_info[0]
+            );
+        }
+
+        // clues for other words are left to the filter
+        let crane = [parse_pattern("crane", "bbbbb").unwrap()];
+        assert!(transitions.lookup(&crane, &words).is_none());
+    }
+
+    #[test]
+    fn trying_a_word_reports_its_split_without_changing_anything() {
+        let words = scored_words(&["crane", "trace", "grace", "slate", "plate"]);
+        // grace tells crane, trace and itself apart, but not slate from plate
+        let report = describe_try(&"grace".to_string(), &words);
+        assert!(
+            report.contains("would split 5 words into 4 groups, leaving at most 2 and"),
+            "{}",
+            report
+        );
+
+        // commands only borrow the candidates and never see the clues, so all
+        // that could change is the scorer
+        let command = Command::parse("try grace").expect("try takes a word");
+        let mut scorer = Scorer::Entropy;
+        command.run(&words, &mut scorer, &Args::parse_from(["solver"]));
+        assert_eq!(scorer, Scorer::Entropy);
+    }
+
+    #[test]
+    fn the_final_options_come_out_in_the_same_order_every_run() {
+        let words = read_word_list(WORDS);
+        // leaves words ending in a?e, several of them scoring the same
+        let known_info = [parse_pattern("crane", "bbgbg").unwrap()];
+        let mut candidates = filter_using_known_info(&words, &known_info);
+        let order = |candidates: &[ScoredWord]| {
+            optimise_results(candidates.to_vec(), &known_info)
+                .into_iter()
+                .map(|sw| (sw.score, sw.word))
+                .collect::<Vec<_>>()
+        };
+        let first = order(&candidates);
+        assert!(
+            first.windows(2).any(|pair| pair[0].0 == pair[1].0),
+            "the clue should leave some ties to break"
+        );
+        for seed in 0..5 {
+            candidates.shuffle(&mut StdRng::seed_from_u64(seed));
+            assert_eq!(order(&candidates), first);
+        }
+        // ties are broken alphabetically
+        assert!(first
+            .windows(2)
+            .all(|pair| pair[0].0 > pair[1].0 || pair[0].1 < pair[1].1));
+    }
+
+    #[test]
+    fn exported_stats_have_a_row_per_word_that_agrees_with_the_totals() {
+        let words = sample_of_bundled_list(200);
+        let scratch = ScratchFile::new("export-stats.csv");
+        analyze(
+            &words,
+            &words,
+            Strategy::FrequencyPositionAware,
+            &["slate"],
+            &Ranking::default(),
+            Some(scratch.path()),
+            None,
+            &mut StdRng::seed_from_u64(0),
+            false,
+            MAX_GUESSES,
+            None,
+        )
+        .unwrap();
+        let csv = std::fs::read_to_string(scratch.path()).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("word,guesses,solved"));
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        assert_eq!(rows.len(), words.len());
+        assert!(rows.iter().all(|row| row.len() == 3));
+
+        let traces = evaluate_first_guess(
+            &words,
+            &words,
+            "slate",
+            &Ranking::default(),
+            false,
+            MAX_GUESSES,
+            None,
+        );
+        let histogram = GuessHistogram::from_traces(&traces, MAX_GUESSES);
+        let solved = rows.iter().filter(|row| row[2] == "true").count();
+        assert_eq!(solved, histogram.solved_count() as usize);
+    }
+
+    #[test]
+    fn the_parallel_histogram_matches_a_serial_count() {
+        let words = sample_of_bundled_list(300);
+        let traces = evaluate_first_guess(
+            &words,
+            &words,
+            "slate",
+            &Ranking::default(),
+            false,
+            MAX_GUESSES,
+            None,
+        );
+        let serial = traces
+            .iter()
+            .fold(GuessHistogram::new(MAX_GUESSES), GuessHistogram::record);
+        assert_eq!(GuessHistogram::from_traces(&traces, MAX_GUESSES), serial);
+        assert_eq!(serial.solved_count() + serial.failed, words.len() as u32);
+    }
+
+    #[test]
+    fn a_claim_verifies_until_it_is_tampered_with() {
+        let words = sample_of_bundled_list(300);
+        let strategy = Strategy::FrequencyPositionAware;
+        let frequencies = positional_frequencies(&words);
+        let opener = get_first_guess_using(
+            &words,
+            &words,
+            strategy,
+            &frequencies,
+            &mut StdRng::seed_from_u64(0),
+        );
+        let claim = OpenerClaim::new(
+            &words,
+            &words,
+            "digest",
+            strategy,
+            &opener,
+            &Ranking::default(),
+            MAX_GUESSES,
+        );
+        // it's checked as it would be read back from a file
+        let json = serde_json::to_string(&claim).unwrap();
+        let read_back = || serde_json::from_str::<OpenerClaim>(&json).unwrap();
+        assert_eq!(
+            read_back().verify(
+                &words,
+                &words,
+                &frequencies,
+                "digest",
+                &Ranking::default(),
+                MAX_GUESSES
+            ),
+            Ok(())
+        );
+
+        let mut edited = read_back();
+        edited.solvable += 1;
+        assert!(edited
+            .verify(
+                &words,
+                &words,
+                &frequencies,
+                "digest",
+                &Ranking::default(),
+                MAX_GUESSES
+            )
+            .is_err());
+        // even with its checksum fixed up, the count is recomputed
+        edited.checksum = edited.compute_checksum();
+        assert_eq!(
+            edited.verify(
+                &words,
+                &words,
+                &frequencies,
+                "digest",
+                &Ranking::default(),
+                MAX_GUESSES
+            ),
+            Err(format!(
+                "{} solves {} words, not {}",
+                opener,
+                claim.solvable,
+                claim.solvable + 1
+            ))
+        );
+
+        assert!(read_back()
+            .verify(
+                &words,
+                &words,
+                &frequencies,
+                "other",
+                &Ranking::default(),
+                MAX_GUESSES
+            )
+            .is_err());
+        assert!(read_back()
+            .verify(
+                &words,
+                &words,
+                &frequencies,
+                "digest",
+                &Ranking::default(),
+                MAX_GUESSES + 1
+            )
+            .is_err());
+
+        let mut edited = read_back();
+        edited.opener = "zzzzz".to_string();
+        edited.checksum = edited.compute_checksum();
+        assert_eq!(
+            edited.verify(
+                &words,
+                &words,
+                &frequencies,
+                "digest",
+                &Ranking::default(),
+                MAX_GUESSES
+            ),
+            Err(format!("{:?} opens with {}, not zzzzz", strategy, opener))
+        );
+    }
+
+    #[test]
+    fn each_random_choice_repeats_for_a_seed_whatever_else_draws() {
+        let words = read_word_list(WORDS);
+        let args = Args::parse_from(["solver", "--seed", "7"]);
+        let random_opener =
+            |args: &Args| get_first_guess(&words, Strategy::Random, &mut seeded_rng(args));
+        let sample = |args: &Args| -> Vec<String> {
+            words
+                .choose_multiple(&mut seeded_rng(args), 10)
+                .map(|sw| sw.word.clone())
+                .collect()
+        };
+
+        let alone = sample(&args);
+        // drawing the random opener first doesn't shift which words are sampled
+        let opener = random_opener(&args);
+        assert_eq!(sample(&args), alone);
+        assert_eq!(random_opener(&args), opener);
+
+        let other = Args::parse_from(["solver", "--seed", "8"]);
+        assert_ne!(sample(&other), alone);
+        assert_ne!(random_opener(&other), opener);
+    }
+
+    #[test]
+    fn the_hint_letter_splits_the_candidates_closest_to_half() {
+        let known_info = [parse_pattern("crane", "bbgbg").unwrap()];
+        // s is in three, t in all four, and p and k in one each, so only l
+        // splits them evenly
+        let words = scored_words(&["slate", "plate", "skate", "state"]);
+        assert_eq!(most_informative_letter(&words, &known_info), Some(('l', 2)));
+        // c splits these evenly too, and would win the tie, but it's been tried
+        let words = scored_words(&["slate", "plate", "grace", "trace"]);
+        assert_eq!(most_informative_letter(&words, &known_info), Some(('l', 2)));
+        assert_eq!(
+            most_informative_letter(&scored_words(&["slate"]), &known_info),
+            None
+        );
+    }
+
+    #[test]
+    fn positional_entropy_matches_hand_worked_values() {
+        let words = scored_words(&["abcde", "abcdf", "abxdg", "abxyh"]);
+        // one letter, two equally likely, three to one, then four equally likely
+        let three_to_one = -(0.75f64 * 0.75f64.log2() + 0.25 * 0.25f64.log2());
+        let expected = [0.0, 0.0, 1.0, three_to_one, 2.0];
+        for (position, (got, want)) in positional_entropy(&words).iter().zip(expected).enumerate() {
+            assert!(
+                (got - want).abs() < 1e-9,
+                "position {} has {} bits, not {}",
+                position + 1,
+                got,
+                want
+            );
+        }
+    }
+
+    #[test]
+    fn switching_strategy_changes_the_next_suggestion() {
+        let words = read_word_list(WORDS);
+        let known_info = [parse_pattern("crane", "bbgbg").unwrap()];
+        let candidates = filter_using_known_info(&words, &known_info);
+        let top = |scorer: Scorer| {
+            scorer.rank(candidates.clone(), &known_info, false, None)[0]
+                .word
+                .clone()
+        };
+
+        let mut scorer = Scorer::Frequency;
+        let before = top(scorer);
+        Command::parse("strategy entropy")
+            .expect("strategy takes a name")
+            .run(&candidates, &mut scorer, &Args::parse_from(["solver"]));
+        assert_eq!(scorer, Scorer::Entropy);
+        assert_ne!(top(scorer), before);
+    }
+
+    #[test]
+    fn with_nothing_to_score_the_candidates_come_back_in_a_stable_order() {
+        let words = read_word_list(WORDS);
+        // every position has had a red tile, so none is scored
+        let known_info = [parse_pattern("jumpy", "bbbbb").unwrap()];
+        assert!(scored_positions(&known_info).is_empty());
+        let mut candidates = filter_using_known_info(&words, &known_info);
+        let order = |candidates: &[ScoredWord]| {
+            optimise_results(candidates.to_vec(), &known_info)
+                .into_iter()
+                .map(|sw| (sw.word, sw.score))
+                .collect::<Vec<_>>()
+        };
+        let first = order(&candidates);
+        assert!(first.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(first.iter().all(|(_, score)| *score == 1));
+        for seed in 0..3 {
+            candidates.shuffle(&mut StdRng::seed_from_u64(seed));
+            assert_eq!(order(&candidates), first);
+        }
+    }
+
+    #[test]
+    fn each_opener_in_the_file_gets_its_own_row_of_stats() {
+        let words = sample_of_bundled_list(300);
+        let evaluations = eval_openers(
+            &words,
+            &words,
+            "slate\n\nqajaq\nnot-a-word\n",
+            &Ranking::default(),
+            false,
+            MAX_GUESSES,
+            &patterns_for(&words),
+        );
+        assert_eq!(
+            evaluations.len(),
+            2,
+            "the blank and invalid lines are skipped"
+        );
+
+        for evaluation in &evaluations {
+            let traces = evaluate_first_guess(
+                &words,
+                &words,
+                &evaluation.opener,
+                &Ranking::default(),
+                false,
+                MAX_GUESSES,
+                None,
+            );
+            let histogram = GuessHistogram::from_traces(&traces, MAX_GUESSES);
+            assert_eq!(evaluation.solvable, histogram.solved_count() as usize);
+            assert_eq!(evaluation.average_guesses, histogram.average_guesses());
+        }
+        // sorted best first, and qajaq is a poor opener
+        assert_eq!(evaluations[0].opener, "slate");
+        assert!(evaluations[0].average_guesses < evaluations[1].average_guesses);
+    }
 
-    (winner.0.clone(), winner.1 .1.clone())
+    #[test]
+    fn a_bigger_sample_gives_a_narrower_interval() {
+        let margins: Vec<f64> = [50, 200, 1000, 5000]
+            .iter()
+            .map(|n| confidence_margin(0.9, *n))
+            .collect();
+        assert!(
+            margins.windows(2).all(|pair| pair[0] > pair[1]),
+            "{:?}",
+            margins
+        );
+        // 1.96 * sqrt(0.9 * 0.1 / 100)
+        assert!((confidence_margin(0.9, 100) - 0.0588).abs() < 1e-12);
+    }
+
+    #[test]
+    fn the_teach_transcript_gives_each_steps_count_and_choice() {
+        let words = read_word_list(WORDS);
+        let scratch = ScratchFile::new("teach.txt");
+        let mut teacher = Teacher::new(Some(scratch.path())).unwrap();
+        let mut known_info = vec![];
+        let mut candidates = words.clone();
+        for (step, (guess, pattern)) in [("crane", "bbgbg"), ("slate", "ybgbg")].iter().enumerate()
+        {
+            known_info.push(parse_pattern(guess, pattern).unwrap());
+            candidates = optimise_results(
+                filter_using_known_info(&candidates, &known_info),
+                &known_info,
+            );
+            teacher
+                .explain_choice(step + 1, &candidates, &known_info, false)
+                .unwrap();
+
+            let transcript = std::fs::read_to_string(scratch.path()).unwrap();
+            let lines: Vec<&str> = transcript.lines().collect();
+            assert_eq!(
+                lines[2 * step],
+                format!("Step {}: {} words left", step + 1, candidates.len())
+            );
+            assert!(
+                lines[2 * step + 1].contains(&candidates[0].word),
+                "{}",
+                lines[2 * step + 1]
+            );
+        }
+    }
+
+    #[test]
+    fn assuming_a_clue_reports_what_it_would_leave_without_recording_it() {
+        let words = read_word_list(WORDS);
+        let known_info = [parse_pattern("crane", "bbgbg").unwrap()];
+        let candidates = filter_using_known_info(&words, &known_info);
+        let with_assumption = [
+            parse_pattern("crane", "bbgbg").unwrap(),
+            parse_pattern("slate", "bbgbg").unwrap(),
+        ];
+        let left = filter_using_known_info(&words, &with_assumption).len();
+        assert!(left > 1 && left < candidates.len());
+        let report = describe_assumption("slate", "bbgbg", &candidates, ClueLetters::default());
+        assert!(
+            report.contains(&format!(" got bbgbg, {} words would be left", left)),
+            "{}",
+            report
+        );
+
+        // the command only borrows the candidates and never sees the clues
+        let before: Vec<String> = candidates.iter().map(|sw| sw.word.clone()).collect();
+        let mut scorer = Scorer::Frequency;
+        Command::parse("assume slate bbgbg")
+            .expect("assume takes a word and a pattern")
+            .run(&candidates, &mut scorer, &Args::parse_from(["solver"]));
+        let after: Vec<String> = candidates.iter().map(|sw| sw.word.clone()).collect();
+        assert_eq!(after, before);
+        assert_eq!(scorer, Scorer::Frequency);
+    }
+
+    #[test]
+    fn repeated_letters_are_only_penalised_while_many_words_remain() {
+        let ranked = |count: usize| {
+            // geese first, then words without repeats, all scoring the same
+            let mut words = scored_words(&["geese"]);
+            words.extend(
+                read_word_list(WORDS)
+                    .into_iter()
+                    .filter(|sw| sw.word.chars().collect::<HashSet<char>>().len() == 5)
+                    .take(count - 1)
+                    .map(|sw| ScoredWord { score: 1, ..sw }),
+            );
+            penalise_repeats(&mut words);
+            words.iter().position(|sw| sw.word == "geese").unwrap()
+        };
+        // early on the distinct letter words go first
+        assert_eq!(ranked(200), 199);
+        // but in the endgame it keeps its place
+        assert_eq!(ranked(8), 0);
+    }
+
+    #[test]
+    fn annealing_gets_close_to_the_best_opener_on_the_list() {
+        let words = sample_of_bundled_list(300);
+        // openers can come from anywhere on the full list, like with --guesses
+        let allowed = read_word_list(WORDS);
+        let cost = |opener: &String| expected_remaining(&partition(opener, &words));
+        let best = allowed
+            .par_iter()
+            .map(|sw| cost(&sw.word))
+            .reduce(|| f64::INFINITY, f64::min);
+
+        let annealed = anneal_opener(&words, &allowed, None, &mut StdRng::seed_from_u64(0));
+        assert!(allowed.iter().any(|sw| sw.word == annealed));
+        assert!(
+            cost(&annealed) <= best * 1.1,
+            "{} leaves {:.2} on average, the best leaves {:.2}",
+            annealed,
+            cost(&annealed),
+            best
+        );
+    }
+
+    #[test]
+    fn coverage_gaps_name_missing_positions_and_missing_letters_once() {
+        // a to e rotated through every position but the last rotation, so each
+        // of them misses exactly one position
+        let words = scored_words(&["abcde", "bcdea", "cdeab", "deabc"]);
+        let gaps = coverage_gaps(&words, 1);
+
+        let positions: Vec<(char, usize, usize)> = gaps
+            .iter()
+            .filter_map(|gap| gap.position.map(|p| (gap.letter, p, gap.words)))
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                ('a', 2, 0),
+                ('b', 3, 0),
+                ('c', 4, 0),
+                ('d', 5, 0),
+                ('e', 1, 0)
+            ]
+        );
+
+        // letters the list never uses come up once, not once per position
+        let missing: String = gaps
+            .iter()
+            .filter(|gap| gap.position.is_none())
+            .map(|gap| gap.letter)
+            .collect();
+        assert_eq!(missing, "fghijklmnopqrstuvwxyz");
+    }
+
+    #[test]
+    fn the_solve_cdf_only_climbs_and_ends_at_the_share_solved() {
+        let words = sample_of_bundled_list(200);
+        // few enough guesses that some words go unsolved
+        let max_guesses = 3;
+        let traces = evaluate_first_guess(
+            &words,
+            &words,
+            "slate",
+            &Ranking::default(),
+            false,
+            max_guesses,
+            None,
+        );
+        let histogram = GuessHistogram::from_traces(&traces, max_guesses);
+        assert!(histogram.failed > 0);
+
+        let cdf = histogram.cdf();
+        assert_eq!(cdf.len(), max_guesses);
+        assert!(cdf.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", cdf);
+        let solvable = traces.iter().filter(|trace| trace.solved).count();
+        assert_eq!(cdf[max_guesses - 1], solvable as f64 / words.len() as f64);
+    }
+
+    #[test]
+    fn the_best_pair_shares_no_letters_and_covers_more_than_either_word() {
+        let words = read_word_list(WORDS);
+        let (first, second) = best_pair(&words, 20).unwrap();
+        // the list has plenty of pairs with ten different letters between them
+        assert!(
+            !first.chars().any(|c| second.contains(c)),
+            "{} and {} share a letter",
+            first,
+            second
+        );
+
+        let frequencies = letter_frequencies(&words);
+        let pair = letter_coverage(&frequencies, &[&first, &second]);
+        let best_single = words
+            .iter()
+            .map(|sw| letter_coverage(&frequencies, &[&sw.word]))
+            .max()
+            .unwrap();
+        assert!(pair > best_single);
+    }
+
+    #[test]
+    fn there_is_no_best_pair_without_two_words() {
+        let words = read_word_list(WORDS);
+        assert_eq!(best_pair(&words[..1], 20), None);
+        assert_eq!(best_pair(&[], 20), None);
+        assert!(best_pair(&words[..2], 20).is_some());
+    }
+
+    #[test]
+    fn the_markdown_transcript_has_a_section_per_guess() {
+        let scratch = ScratchFile::new("transcript.md");
+        let mut transcript = MarkdownTranscript::new(scratch.path(), Palette::Standard);
+        let steps = [("crane", "bbgbg", 40), ("slate", "ybgbg", 1)];
+        for (guess, pattern, remaining) in steps {
+            let clue = parse_pattern(guess, pattern).unwrap();
+            transcript
+                .record(guess, &clue, remaining, "picked for a reason".to_string())
+                .unwrap();
+        }
+
+        let md = std::fs::read_to_string(scratch.path()).unwrap();
+        let sections: Vec<&str> = md.split("\n## ").skip(1).collect();
+        assert_eq!(sections.len(), steps.len());
+        for (i, ((guess, pattern, remaining), section)) in steps.iter().zip(sections).enumerate() {
+            let emoji = parse_pattern(guess, pattern)
+                .unwrap()
+                .emoji(Palette::Standard);
+            let lines: Vec<&str> = section.lines().collect();
+            assert_eq!(lines[0], format!("Guess {}: `{}`", i + 1, guess));
+            assert_eq!(lines[2], emoji);
+            assert!(
+                lines[4].starts_with(&format!("{} word", remaining)),
+                "{}",
+                lines[4]
+            );
+        }
+    }
+
+    #[test]
+    fn solves_follow_the_sessions_ranking() {
+        let words = sample_of_bundled_list(300);
+        let answer = words[0].word.clone();
+        let clue = [calculate_guess_result(&answer, &"slate".to_string())];
+        for scorer in [Scorer::Frequency, Scorer::Entropy, Scorer::Minimax] {
+            let ranking = Ranking {
+                scorer,
+                ..Ranking::default()
+            };
+            let trace = solve_word(
+                &words,
+                &words,
+                &["slate"],
+                &answer,
+                &ranking,
+                false,
+                MAX_GUESSES,
+                None,
+            );
+            let candidates = ranking.rank(filter_using_known_info(&words, &clue), &clue);
+            let expected = next_guess(&candidates, &words, &["slate"], 1, false);
+            assert_eq!(trace.guesses[1], expected, "with {:?}", scorer);
+        }
+    }
+
+    #[test]
+    fn hard_mode_skips_a_second_opener_that_breaks_the_clues() {
+        let words = read_word_list(WORDS);
+        let solve = |hard| {
+            solve_word(
+                &words,
+                &words,
+                &["slate", "crony"],
+                &"sauce".to_string(),
+                &Ranking::default(),
+                hard,
+                MAX_GUESSES,
+                None,
+            )
+        };
+        // crony has none of the letters slate found, which hard mode can't allow
+        assert_eq!(solve(false).guesses[1], "crony");
+        let trace = solve(true);
+        let clue = [calculate_guess_result(
+            &"sauce".to_string(),
+            &"slate".to_string(),
+        )];
+        assert_ne!(trace.guesses[1], "crony");
+        assert_eq!(
+            filter_using_known_info(&scored_words(&[&trace.guesses[1]]), &clue).len(),
+            1,
+            "{} doesn't fit the first clue",
+            trace.guesses[1]
+        );
+    }
+
+    /// Collects what the logger writes, noting whether the spinner was cleared
+    /// each time it was written to
+    struct SpinnerCheckingWriter {
+        status: &'static Status,
+        written: std::sync::Arc<std::sync::Mutex<(Vec<u8>, Vec<bool>)>>,
+    }
+
+    impl Write for SpinnerCheckingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            // the status isn't locked while a record is written, and the work in
+            // progress is still there, just without its spinner
+            let cleared = matches!(self.status.0.try_lock().as_deref(), Ok(Some((_, None))));
+            let mut written = self.written.lock().unwrap();
+            written.0.extend_from_slice(buf);
+            written.1.push(cleared);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn log_records_clear_the_spinner_and_it_comes_back_after() {
+        // its own status, so spinners from tests running alongside don't count
+        let status: &'static Status = Box::leak(Box::new(Status(std::sync::Mutex::new(None))));
+        let written = std::sync::Arc::default();
+        let logger = Logger {
+            status,
+            out: std::sync::Mutex::new(Box::new(SpinnerCheckingWriter {
+                status,
+                written: std::sync::Arc::clone(&written),
+            })),
+        };
+
+        let mut sp = Progress::start_on(status, "working");
+        logger.write(
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .args(format_args!("first"))
+                .build(),
+        );
+        // a record logged while the spinner is already suspended doesn't wait on it
+        sp.suspend(|| {
+            logger.write(
+                &log::Record::builder()
+                    .level(log::Level::Warn)
+                    .args(format_args!("second"))
+                    .build(),
+            )
+        });
+        assert!(matches!(
+            status.0.lock().unwrap().as_ref(),
+            Some((text, _)) if text == "working"
+        ));
+        drop(sp);
+        assert!(status.0.lock().unwrap().is_none());
+
+        let written = written.lock().unwrap();
+        let lines = String::from_utf8_lossy(&written.0);
+        assert_eq!(lines.lines().collect::<Vec<_>>(), ["first", "second"]);
+        assert!(!written.1.is_empty() && written.1.iter().all(|cleared| *cleared));
+    }
 }
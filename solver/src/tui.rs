@@ -0,0 +1,326 @@
+//! A full screen front end for a session, as an alternative to answering the
+//! prompts line by line. The filtering and ranking come from the library, the
+//! same as a session's, with the scorer picked by `--scorer`.
+
+use ratatui::{
+    crossterm::{
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+            KeyModifiers, MouseButton, MouseEventKind,
+        },
+        execute,
+    },
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+use smooth::Smooth;
+use solver::{
+    filter_using_known_info, next_guess, Character, GuessResult, Palette, Ranking, ScoredWord,
+};
+
+/// Each tile is drawn as its letter padded by a space either side, with a gap
+/// between tiles
+const TILE_WIDTH: u16 = 4;
+
+/// A session in progress, as the TUI shows it
+struct Tui<'a> {
+    all_words: &'a [ScoredWord],
+    openers: &'a [&'a str],
+    /// How the candidates are ordered after each clue
+    ranking: Ranking<'a>,
+    /// Whether only guesses that could be the answer are suggested
+    hard: bool,
+    /// The colours tiles are drawn in
+    palette: Palette,
+    /// The candidates left, best suggestion first
+    words: Vec<ScoredWord>,
+    known_info: Vec<GuessResult>,
+    /// The candidates from before each clue, for undoing it
+    history: Vec<Vec<ScoredWord>>,
+    /// The guess being given a clue, with the colour picked for each tile so far
+    row: [Character; 5],
+    /// Which tile of `row` the arrow keys and typing change
+    cursor: usize,
+    /// How far the suggestion list is scrolled
+    scroll: usize,
+    /// Where `row` was last drawn, so clicks can be matched to its tiles
+    row_area: Rect,
+    message: String,
+}
+
+impl<'a> Tui<'a> {
+    /// Sets up the next row for `guess`, with every tile not in the word until
+    /// told otherwise
+    fn start_row(&mut self, guess: &str) {
+        for (tile, c) in self.row.iter_mut().zip(guess.chars()) {
+            *tile = Character::Red(c);
+        }
+        self.cursor = 0;
+        self.scroll = 0;
+    }
+
+    /// The current row as a clue
+    fn clue(&self) -> GuessResult {
+        GuessResult(self.row)
+    }
+
+    /// Which candidates would be left if the current row were the clue
+    fn preview(&self) -> Vec<ScoredWord> {
+        filter_using_known_info(&self.words, &[self.clue()])
+    }
+
+    /// Moves a tile on to the next colour, from not in the word to yellow to
+    /// green and round again
+    fn cycle(&mut self, i: usize) {
+        self.row[i] = match self.row[i] {
+            Character::Red(c) => Character::Yellow(c),
+            Character::Yellow(c) => Character::Green(c),
+            Character::Green(c) => Character::Red(c),
+            other => other,
+        };
+    }
+
+    /// Changes the letter of the selected tile, keeping its colour, and moves on
+    /// to the next
+    fn type_letter(&mut self, letter: char) {
+        self.row[self.cursor] = match self.row[self.cursor] {
+            Character::Yellow(_) => Character::Yellow(letter),
+            Character::Green(_) => Character::Green(letter),
+            _ => Character::Red(letter),
+        };
+        self.cursor = (self.cursor + 1).min(4);
+    }
+
+    /// Records the current row as a clue and ranks what's left, unless it rules
+    /// out every word
+    fn submit(&mut self) {
+        if self
+            .known_info
+            .last()
+            .is_some_and(|clue| clue.0.iter().all(|c| matches!(c, Character::Green(_))))
+        {
+            // already solved, there's nothing more to give a clue for
+            return;
+        }
+        let filtered_results = self.preview();
+        if filtered_results.is_empty() {
+            self.message = "No words match these clues — did you mistype a colour?".to_string();
+            return;
+        }
+        let solved = self.row.iter().all(|c| matches!(c, Character::Green(_)));
+        self.history.push(std::mem::take(&mut self.words));
+        self.known_info.push(self.clue());
+        self.words = self.ranking.rank(filtered_results, &self.known_info);
+        if solved {
+            self.message = format!("Solved in {}!", self.known_info.len());
+            return;
+        }
+        let guess = next_guess(
+            &self.words,
+            self.all_words,
+            self.openers,
+            self.known_info.len(),
+            self.hard,
+        );
+        self.start_row(&guess);
+        self.message.clear();
+    }
+
+    /// Takes back the last clue, so its row can be given again
+    fn undo(&mut self) {
+        let (Some(words), Some(clue)) = (self.history.pop(), self.known_info.pop()) else {
+            self.message = "Nothing to undo yet".to_string();
+            return;
+        };
+        self.words = words;
+        self.row = clue.0;
+        self.cursor = 0;
+        self.scroll = 0;
+        self.message.clear();
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(8), Constraint::Length(1)]).areas(frame.area());
+        let [guesses, suggestions] =
+            Layout::horizontal([Constraint::Length(5 * TILE_WIDTH + 3), Constraint::Min(20)])
+                .areas(main);
+
+        let palette = self.palette;
+        let mut lines: Vec<Line> = self
+            .known_info
+            .iter()
+            .map(|clue| tiles_line(&clue.0, None, palette))
+            .collect();
+        self.row_area = Rect {
+            x: guesses.x + 1,
+            y: guesses.y + 1 + lines.len() as u16,
+            width: 5 * TILE_WIDTH,
+            height: 1,
+        };
+        lines.push(tiles_line(&self.row, Some(self.cursor), palette));
+        lines.push(Line::default());
+        let left = self.preview().len();
+        lines.push(Line::from(format!(
+            "{} {} left",
+            left,
+            if left == 1 { "word" } else { "words" }
+        )));
+        lines.push(Line::from(self.message.as_str()).style(Style::new().fg(Color::Red)));
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" Guesses ")),
+            guesses,
+        );
+
+        let total_score = self.words.iter().map(|sw| sw.score).sum::<usize>().max(1);
+        let visible = suggestions.height.saturating_sub(2) as usize;
+        self.scroll = self
+            .scroll
+            .min(self.words.len().saturating_sub(visible.max(1)));
+        let lines: Vec<Line> = self
+            .words
+            .iter()
+            .skip(self.scroll)
+            .take(visible)
+            .map(|sw| {
+                Line::from(format!(
+                    "{} {}%",
+                    sw.word,
+                    (100.0 * sw.score as f64 / total_score as f64).smooth_str()
+                ))
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(Block::bordered().title(format!(" {} possible words ", self.words.len()))),
+            suggestions,
+        );
+
+        frame.render_widget(
+            Paragraph::new(
+                "←→ tile  ↑↓/space/click colour  a-z letter  enter submit  pgup/pgdn scroll  ctrl-z undo  esc quit",
+            )
+            .style(Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+
+    /// Handles a key press or click, returning `false` once the user quits
+    fn handle(&mut self, event: Event) -> bool {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => return false,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return false
+                }
+                KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => self.undo(),
+                KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+                KeyCode::Right => self.cursor = (self.cursor + 1).min(4),
+                KeyCode::Up | KeyCode::Down | KeyCode::Char(' ') => self.cycle(self.cursor),
+                KeyCode::Char(c) if c.is_ascii_alphabetic() => {
+                    self.type_letter(c.to_ascii_lowercase())
+                }
+                KeyCode::Enter => self.submit(),
+                KeyCode::PageDown => self.scroll += 10,
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+                _ => {}
+            },
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left)
+                    if self
+                        .row_area
+                        .contains(Position::new(mouse.column, mouse.row)) =>
+                {
+                    self.cursor = ((mouse.column - self.row_area.x) / TILE_WIDTH).min(4) as usize;
+                    self.cycle(self.cursor);
+                }
+                MouseEventKind::ScrollDown => self.scroll += 1,
+                MouseEventKind::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+                _ => {}
+            },
+            _ => {}
+        }
+        true
+    }
+}
+
+/// A row of tiles coloured by their clue, with the selected one, if any,
+/// underlined
+fn tiles_line(tiles: &[Character; 5], cursor: Option<usize>, palette: Palette) -> Line<'static> {
+    let spans: Vec<Span> = tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, tile)| {
+            let (letter, background) = match *tile {
+                Character::Green(c) => (c, tile_colour(palette, true)),
+                Character::Yellow(c) => (c, tile_colour(palette, false)),
+                Character::Red(c) => (c, Color::DarkGray),
+                Character::Wild => ('*', Color::Magenta),
+                Character::Empty => (' ', Color::Reset),
+            };
+            let mut style = Style::new()
+                .fg(Color::Black)
+                .bg(background)
+                .add_modifier(Modifier::BOLD);
+            if cursor == Some(i) {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            [
+                Span::styled(format!(" {} ", letter.to_ascii_uppercase()), style),
+                Span::raw(" "),
+            ]
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// The background of a green tile, or a yellow one if not `green`
+fn tile_colour(palette: Palette, green: bool) -> Color {
+    match (palette, green) {
+        (Palette::Standard, true) => Color::Green,
+        (Palette::Standard, false) => Color::Yellow,
+        (Palette::HighContrast, true) => Color::Rgb(245, 121, 58),
+        (Palette::HighContrast, false) => Color::LightBlue,
+    }
+}
+
+/// Runs a session in the TUI until the user quits, or the terminal fails
+pub fn run(
+    words: Vec<ScoredWord>,
+    all_words: &[ScoredWord],
+    openers: &[&str],
+    ranking: Ranking,
+    hard: bool,
+    palette: Palette,
+) -> std::io::Result<()> {
+    let mut tui = Tui {
+        all_words,
+        openers,
+        words: ranking.rank(words, &[]),
+        ranking,
+        hard,
+        palette,
+        known_info: vec![],
+        history: vec![],
+        row: [Character::Empty; 5],
+        cursor: 0,
+        scroll: 0,
+        row_area: Rect::default(),
+        message: String::new(),
+    };
+    tui.start_row(openers[0]);
+
+    let mut terminal = ratatui::init();
+    // the terminal is restored even if drawing fails, so the shell isn't left raw
+    let result = execute!(std::io::stdout(), EnableMouseCapture).and_then(|_| loop {
+        terminal.draw(|frame| tui.draw(frame))?;
+        if !tui.handle(event::read()?) {
+            break execute!(std::io::stdout(), DisableMouseCapture);
+        }
+    });
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,83 @@
+//! Runs the solver binary, for what only shows from outside: what it prints
+//! and the exit codes scripts rely on
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the solver on a small word list with `args` and `input` on stdin,
+/// caching into a scratch directory so the user's cache is left alone
+fn solver(name: &str, args: &[&str], input: &str) -> std::process::Output {
+    let dir = std::env::temp_dir().join(format!("solver-cli-{}", name));
+    std::fs::create_dir_all(&dir).unwrap();
+    let word_list = dir.join("words.txt");
+    std::fs::write(&word_list, "crane\nslate\ntrace\nplate\ngrace\n").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_solver"))
+        .arg(&word_list)
+        // skips testing every strategy, and opens with aecrt
+        .args(["--strategy", "frequency-simple"])
+        .args(args)
+        .env("XDG_CACHE_HOME", dir.join("cache"))
+        .env("HOME", &dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn contradictory_clues_exit_with_code_2_and_print_nothing() {
+    // only crane fits the first clue, and the second rules it out
+    let output = solver("contradiction", &["--batch"], "crane ggggg\nslate ggggg\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("up to guess 2"));
+}
+
+#[test]
+fn clues_that_fit_print_just_the_next_guess() {
+    let output = solver("fits", &["--batch"], "crane ggggg\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "crane\n");
+}
+
+#[test]
+fn giving_up_names_the_likeliest_words_and_ends_the_session() {
+    // aecrt's clue leaves slate and plate, and the clue after giveup is never read
+    let output = solver("giveup", &[], "yybby\ny\ngiveup\nggggg\n");
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let last = stdout.lines().last().unwrap();
+    assert!(last.contains("Giving up"), "{}", stdout);
+    assert!(last.contains("plate") && last.contains("slate"), "{}", last);
+}
+
+#[test]
+fn over_long_input_is_truncated_only_with_truncate_long() {
+    // an empty result asks for each colour separately, yellows first
+    let output = solver("truncate", &["--truncate-long"], "\naecrtzz\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Too many characters, only using aecrt"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Please enter exactly"), "{}", stdout);
+
+    let output = solver("reprompt", &[], "\naecrtzz\naecrt\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Please enter exactly 5 characters.\n>> "),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Too many characters"), "{}", stdout);
+}
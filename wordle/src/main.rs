@@ -1,33 +1,334 @@
-use std::io::Write;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io::{IsTerminal, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use colored::Colorize;
+use clap::Parser;
+use colored::{ColoredString, Colorize};
+use directories::ProjectDirs;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use solver::Palette;
 
 const WORDS: &str = include_str!("words.txt");
 
+/// How many guesses the player gets, the same as the NYT game
+const MAX_GUESSES: usize = 6;
+
+/// With `--did-you-mean`, the most letters a word can differ from a rejected
+/// guess by and still be suggested. Any more and it's a different word, not a typo
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+#[derive(Parser)]
+#[command(about = "A CLI version of Wordle")]
+struct Args {
+    /// Play today's word, the same for everyone playing on the same UTC day
+    #[arg(long)]
+    daily: bool,
+
+    /// Play the daily word from a past day, given as YYYY-MM-DD
+    #[arg(long, value_name = "YYYY-MM-DD", value_parser = parse_date)]
+    date: Option<String>,
+
+    /// Draw tiles in orange and blue instead of green and yellow. The palette
+    /// can also be picked with `WORDLE_PALETTE=high-contrast`
+    #[arg(long)]
+    colorblind: bool,
+
+    /// How common the word to guess is: easy only picks common words, hard any
+    /// word at all. Needs a count after each word in words.txt saying how common
+    /// it is
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Difficulty::Normal,
+        conflicts_with_all = ["daily", "date"]
+    )]
+    difficulty: Difficulty,
+
+    /// How many letters the word to guess has. Only words of this length are
+    /// played with, whatever else is in words.txt
+    #[arg(
+        long,
+        default_value_t = 5,
+        value_parser = parse_length,
+        conflicts_with_all = ["daily", "date"]
+    )]
+    length: usize,
+
+    /// Play Absurdle: no word is picked up front, and each guess gets whichever
+    /// colours leave the most words still possible. These games aren't counted
+    /// in your stats
+    #[arg(long, conflicts_with_all = ["daily", "date"])]
+    absurdle: bool,
+
+    /// Play a harder variant where letters in the wrong position look the same
+    /// as letters that aren't in the word at all. `NO_YELLOW` still turns it on
+    /// too
+    #[arg(long)]
+    no_yellow: bool,
+
+    /// When a guess isn't in the word list, suggest the closest one or two
+    /// words that are. Finding them means comparing it with every word
+    #[arg(long)]
+    did_you_mean: bool,
+}
+
 // A CLI version of Wordle
 fn main() {
-    let words: Vec<&str> = WORDS.split_whitespace().collect();
-    let word = words[rand::random::<usize>() % words.len()];
+    let args = Args::parse();
+    // escape codes are just noise when piped, or when asked not to use them
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+        || !std::io::stdout().is_terminal()
+    {
+        colored::control::set_override(false);
+    }
+
+    let entries = words_of_length(read_words(WORDS), args.length);
+    if entries.is_empty() {
+        eprintln!(
+            "{}",
+            format!("words.txt has no {} letter words to play with", args.length).red()
+        );
+        std::process::exit(1);
+    }
+    // guesses are checked against this, so it's built once up front. every word
+    // can be guessed, whichever can be the answer
+    let dictionary: HashSet<&str> = entries.iter().map(|(word, _)| *word).collect();
+    if args.difficulty != Difficulty::Normal && entries.iter().all(|(_, count)| count.is_none()) {
+        eprintln!(
+            "{}",
+            "words.txt doesn't say how common each word is, so every difficulty plays the same"
+                .yellow()
+        );
+    }
+    let answers = args.difficulty.answers(&entries);
+    let today = today_utc();
+    let day = match args.date {
+        Some(date) if date > today => {
+            eprintln!("{}", format!("{} hasn't happened yet", date).red());
+            std::process::exit(1);
+        }
+        Some(date) => Some(date),
+        None => args.daily.then_some(today),
+    };
+    // word selection gets its own rng, so nothing else drawing random numbers can
+    // change which word is picked. the daily word comes from an rng seeded by the
+    // date, so it's the same for everyone. that only holds while everyone draws
+    // from the same answers, which is why daily games can't change the
+    // difficulty or length
+    let mut rng = match &day {
+        Some(day) => StdRng::seed_from_u64(date_seed(day)),
+        None => StdRng::from_entropy(),
+    };
+    if let Some(day) = &day {
+        println!("{}", format!("Daily word for {}", day).black());
+    }
+
+    // `NO_YELLOW` hid yellows before there was a flag for it, so it still does
+    let hide_yellow = args.no_yellow || std::env::var("NO_YELLOW").is_ok();
+    if hide_yellow {
+        println!(
+            "{}",
+            "Yellows are hidden, so only right letters in the right place show.".black()
+        );
+    }
+
+    // `COLOR_BLIND` picked orange and blue squares before there were palettes,
+    // so it still does
+    let palette = if args.colorblind || std::env::var("COLOR_BLIND").is_ok() {
+        Palette::HighContrast
+    } else {
+        match std::env::var("WORDLE_PALETTE") {
+            Ok(name) => Palette::parse(&name).unwrap_or_else(|| {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Unknown palette {}, expected standard or high-contrast",
+                        name
+                    )
+                    .yellow()
+                );
+                Palette::Standard
+            }),
+            Err(_) => Palette::Standard,
+        }
+    };
+
+    let stats_path = stats_path();
+    loop {
+        let host = if args.absurdle {
+            Host::Absurdle(answers.clone())
+        } else {
+            Host::Word(answers[rng.gen_range(0..answers.len())])
+        };
+        let solved_in = play(
+            host,
+            &dictionary,
+            hide_yellow,
+            palette,
+            args.difficulty,
+            args.did_you_mean,
+        );
+
+        // the host can always dodge a guess while more than one word is left,
+        // so absurdle games would only drag the stats down
+        if !args.absurdle {
+            let mut stats = Stats::load(&stats_path);
+            if stats.record(day.as_deref(), solved_in) {
+                stats.save(&stats_path);
+            } else if let Some(day) = &day {
+                println!(
+                    "\n{}",
+                    format!("You've played {} before, so this game isn't counted", day).black()
+                );
+            }
+            println!("\n{}", stats.summary(solved_in));
+        }
+
+        // there's only one word a day, so there's no next game to offer
+        if day.is_some() {
+            break;
+        }
+        println!("\nPlay again? (y)");
+        if !matches!(read_line().as_str(), "y" | "") {
+            break;
+        }
+    }
+}
+
+/// Who the player is guessing against
+enum Host<'a> {
+    /// An ordinary game, with the word picked before the first guess
+    Word(&'a str),
+    /// Absurdle's host, which only keeps track of the words that fit every clue
+    /// so far, so it can answer each guess with whichever clue keeps the most
+    Absurdle(Vec<&'a str>),
+}
+
+impl<'a> Host<'a> {
+    /// The word to reveal at the end. Absurdle's host never settles on one, so
+    /// it owns up to the first that fits every clue it gave
+    fn word(&self) -> &'a str {
+        match self {
+            Host::Word(word) => word,
+            Host::Absurdle(candidates) => candidates[0],
+        }
+    }
 
-    println!("I have a 5 letter word in mind. Can you guess it?");
-    let mut chances_left = 5;
+    /// Colours `input`. Absurdle's host groups its words by the colours they'd
+    /// give, keeps the biggest group and answers with its colours
+    fn answer(&mut self, input: &str, hide_yellow: bool) -> Vec<Character> {
+        let candidates = match self {
+            Host::Word(word) => return score_guess(word, input, hide_yellow),
+            Host::Absurdle(candidates) => candidates,
+        };
+        let mut buckets: BTreeMap<Vec<u8>, (Vec<Character>, Vec<&'a str>)> = BTreeMap::new();
+        for candidate in candidates.iter() {
+            let tiles = score_guess(candidate, input, hide_yellow);
+            buckets
+                .entry(pattern_code(&tiles))
+                .or_insert_with(|| (tiles, vec![]))
+                .1
+                .push(candidate);
+        }
+        // between groups the same size, the one whose colours give the least
+        // away wins, then the first by key, so the same guesses always get the
+        // same colours
+        let (_, (tiles, kept)) = buckets
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, (tiles, kept))| {
+                (
+                    kept.len(),
+                    std::cmp::Reverse(tiles.iter().map(|t| t.rank()).sum::<u8>()),
+                )
+            })
+            .expect("there's always a word left, since one is kept each guess");
+        *candidates = kept;
+        tiles
+    }
+}
+
+/// Plays one game against `host`, returning how many guesses it took, or
+/// `None` if the player ran out of chances or gave up. With `did_you_mean`,
+/// guesses that aren't words get the closest that are suggested
+fn play(
+    mut host: Host,
+    dictionary: &HashSet<&str>,
+    hide_yellow: bool,
+    palette: Palette,
+    difficulty: Difficulty,
+    did_you_mean: bool,
+) -> Option<usize> {
+    let length = host.word().chars().count();
+    match host {
+        Host::Word(_) => println!(
+            "I have a {} letter word in mind, on {} difficulty. Can you guess it in {} tries? Type 'giveup' to reveal it.",
+            length,
+            format!("{:?}", difficulty).to_lowercase(),
+            MAX_GUESSES
+        ),
+        Host::Absurdle(_) => println!(
+            "I haven't picked a {} letter word, on {} difficulty, and I'll dodge your guesses for as long as I can. Can you pin one down in {} tries? Type 'giveup' to see one that fits.",
+            length,
+            format!("{:?}", difficulty).to_lowercase(),
+            MAX_GUESSES
+        ),
+    }
+    let mut chances_left = MAX_GUESSES;
 
     if std::env::var("DEBUG").is_ok() {
-        println!("(debug: {})", word.blue());
+        match &host {
+            Host::Word(word) => println!("(debug: {})", word.blue()),
+            Host::Absurdle(candidates) => println!("(debug: {} words)", candidates.len()),
+        }
     }
 
-    // loop until the user guesses the word or runs out of chances
+    // the best colour seen so far for each letter guessed
+    let mut keyboard: HashMap<char, Character> = HashMap::new();
+    // every guess's colours, for the share grid
+    let mut history: Vec<Vec<Character>> = vec![];
+
+    // loop until the user guesses the word, runs out of chances or gives up
     loop {
-        match process_input(word, read_line()) {
-            Ok(win) => {
+        let input = read_line();
+        if input == "giveup" {
+            println!("You gave up. The word was {}!", host.word().blue());
+            println!("\n{}", share_grid(&history, false, palette));
+            return None;
+        }
+        match process_input(&mut host, &input, dictionary, hide_yellow, palette) {
+            Ok(tiles) => {
+                if !colored::control::SHOULD_COLORIZE.should_colorize() {
+                    // without colours the letters alone don't say anything, so
+                    // show the squares too
+                    println!("{}", emoji_row(&tiles, palette));
+                }
+                for tile in &tiles {
+                    let best = keyboard.entry(tile.letter()).or_insert(*tile);
+                    // a letter never goes back from green to yellow, or yellow to red
+                    if tile.rank() > best.rank() {
+                        *best = *tile;
+                    }
+                }
+                print_keyboard(&keyboard, palette);
+                let win = tiles.iter().all(|t| matches!(t, Character::Green(_)));
+                history.push(tiles);
+
                 if win {
                     println!("You guessed it right!");
-                    break;
+                    println!("\n{}", share_grid(&history, win, palette));
+                    return Some(history.len());
                 } else {
                     chances_left -= 1;
                     if chances_left == 0 {
-                        println!("You ran out of chances. The word was {}!", word.blue());
-                        break;
+                        println!(
+                            "You ran out of chances. The word was {}!",
+                            host.word().blue()
+                        );
+                        println!("\n{}", share_grid(&history, win, palette));
+                        return None;
                     } else {
                         println!(
                             "{} You have {} chances left.",
@@ -38,49 +339,474 @@ fn main() {
                 }
             }
             Err(ProcessInputError::InvalidLength) => {
-                println!("Please enter a word of length {}", word.len())
+                println!("Please enter a word of length {}", length)
+            }
+            Err(ProcessInputError::NotInWordList) => {
+                let suggestions = match did_you_mean {
+                    true => closest_words(&input, dictionary),
+                    false => vec![],
+                };
+                if suggestions.is_empty() {
+                    println!("That's not in the word list, try another word")
+                } else {
+                    let suggestions: Vec<String> =
+                        suggestions.iter().map(|w| w.blue().to_string()).collect();
+                    println!(
+                        "That's not in the word list, did you mean {}?",
+                        suggestions.join(" or ")
+                    )
+                }
             }
         }
     }
 }
 
+/// How the player has done over every finished game, kept between runs
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct Stats {
+    played: u32,
+    wins: u32,
+    current_streak: u32,
+    max_streak: u32,
+    /// How many wins took each number of guesses, from 1 up
+    guesses: [u32; MAX_GUESSES],
+    /// The days whose daily word has been played, as YYYY-MM-DD, so replaying
+    /// one isn't counted again
+    #[serde(default)]
+    daily: BTreeSet<String>,
+}
+
+impl Stats {
+    /// Reads the stats file, starting afresh if there isn't one yet or it
+    /// can't be read
+    fn load(path: &str) -> Stats {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Stats::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|_| {
+            eprintln!(
+                "{}",
+                "Your stats couldn't be read, so they're starting again".yellow()
+            );
+            Stats::default()
+        })
+    }
+
+    fn save(&self, path: &str) {
+        let json = serde_json::to_string_pretty(self).expect("Could not serialise stats");
+        if std::fs::write(path, json).is_err() {
+            eprintln!("{}", "Your stats couldn't be saved".yellow());
+        }
+    }
+
+    /// Adds a finished game, won in `solved_in` guesses or lost if `None`.
+    /// A daily game is only counted the first time its `day` is played, and
+    /// returns whether it was
+    fn record(&mut self, day: Option<&str>, solved_in: Option<usize>) -> bool {
+        if let Some(day) = day {
+            if !self.daily.insert(day.to_string()) {
+                return false;
+            }
+        }
+        self.played += 1;
+        match solved_in {
+            Some(guesses) => {
+                self.wins += 1;
+                self.current_streak += 1;
+                self.max_streak = self.max_streak.max(self.current_streak);
+                self.guesses[guesses - 1] += 1;
+            }
+            None => self.current_streak = 0,
+        }
+        true
+    }
+
+    /// The totals and a bar for each number of guesses, with this game's
+    /// highlighted
+    fn summary(&self, solved_in: Option<usize>) -> String {
+        let mut summary = format!(
+            "{}\nPlayed {} | Win % {} | Current streak {} | Max streak {}\n\n{}",
+            "STATISTICS".bold(),
+            self.played,
+            100 * self.wins / self.played.max(1),
+            self.current_streak,
+            self.max_streak,
+            "GUESS DISTRIBUTION".bold()
+        );
+        let most = self.guesses.iter().copied().max().unwrap_or(0).max(1);
+        for (i, count) in self.guesses.iter().enumerate() {
+            let bar = "█".repeat((20 * count / most) as usize);
+            let bar = if solved_in == Some(i + 1) {
+                bar.green()
+            } else {
+                bar.black()
+            };
+            summary.push_str(&format!("\n{}: {} {}", i + 1, bar, count));
+        }
+        summary
+    }
+}
+
+/// Where the stats are kept, alongside the solver's cache
+fn stats_path() -> String {
+    let cache_dir = ProjectDirs::from("com", "617a7a", "wordle")
+        .expect("Could not find config directory")
+        .cache_dir()
+        .to_str()
+        .expect("Could not convert config directory to string")
+        .to_string();
+    // if this fails, saving the stats will too, which only warns
+    let _ = std::fs::create_dir_all(&cache_dir);
+    format!("{}/stats.json", cache_dir)
+}
+
+/// Today's date in UTC, as YYYY-MM-DD
+fn today_utc() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    // days since 1970-01-01 to a civil date, counting years from March so the
+    // leap day comes last
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Reads a date given as YYYY-MM-DD, checking the day exists
+fn parse_date(date: &str) -> Result<String, String> {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return Err("expected a date like 2022-01-31".to_string());
+    };
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return Err("expected a date like 2022-01-31".to_string());
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return Err(format!("there's no month {}", month)),
+    };
+    if !(1..=days_in_month).contains(&day) {
+        return Err(format!("month {} doesn't have a day {}", month, day));
+    }
+    // written out the same way as today's date, so they compare and seed alike
+    Ok(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Hashes a YYYY-MM-DD date into a seed with FNV-1a, which unlike std's hasher
+/// gives the same result on every machine and Rust version
+fn date_seed(date: &str) -> u64 {
+    date.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The colour a letter of a guess comes back as
+#[derive(Clone, Copy)]
+enum Character {
+    /// The letter is in the word, but not in this position
+    Yellow(char),
+    /// The letter is in the word, in this position
+    Green(char),
+    /// The letter is not in the word
+    Red(char),
+}
+
+impl Character {
+    fn letter(&self) -> char {
+        match self {
+            Character::Yellow(c) | Character::Green(c) | Character::Red(c) => *c,
+        }
+    }
+
+    /// How much the colour tells you about the letter, so the keyboard can keep
+    /// the most useful one
+    fn rank(&self) -> u8 {
+        match self {
+            Character::Red(_) => 0,
+            Character::Yellow(_) => 1,
+            Character::Green(_) => 2,
+        }
+    }
+
+    /// The letter, coloured as its tile is in `palette`
+    fn paint(&self, palette: Palette) -> ColoredString {
+        let letter = self.letter().to_string();
+        match self {
+            Character::Yellow(_) => palette.yellow(&letter),
+            Character::Green(_) => palette.green(&letter),
+            Character::Red(_) => palette.red(&letter),
+        }
+    }
+}
+
+/// Reads `words.txt`, one word per line, each optionally followed by a count of
+/// how common it is
+fn read_words(contents: &str) -> Vec<(&str, Option<u64>)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let word = fields.next()?;
+            Some((word, fields.next().and_then(|count| count.parse().ok())))
+        })
+        .collect()
+}
+
+/// Keeps the words `length` letters long. Words with letters that aren't ascii
+/// are skipped with a warning, since the tiles are matched up letter by letter
+fn words_of_length(entries: Vec<(&str, Option<u64>)>, length: usize) -> Vec<(&str, Option<u64>)> {
+    let (entries, skipped): (Vec<_>, Vec<_>) =
+        entries.into_iter().partition(|(word, _)| word.is_ascii());
+    if !skipped.is_empty() {
+        eprintln!(
+            "{}",
+            format!(
+                "Skipped {} {} in words.txt with letters that aren't ascii",
+                skipped.len(),
+                if skipped.len() == 1 { "word" } else { "words" }
+            )
+            .yellow()
+        );
+    }
+    entries
+        .into_iter()
+        .filter(|(word, _)| word.len() == length)
+        .collect()
+}
+
+/// Reads a word length, which has to leave at least one letter to guess
+fn parse_length(length: &str) -> Result<usize, String> {
+    match length.parse::<usize>() {
+        Ok(0) => Err("the word needs at least one letter".to_string()),
+        Ok(length) => Ok(length),
+        Err(_) => Err(format!("{} isn't a number", length)),
+    }
+}
+
+/// How obscure the word to guess can be
+#[derive(Clone, Copy, PartialEq, Debug, clap::ValueEnum)]
+enum Difficulty {
+    /// Only the most common quarter of words
+    Easy,
+    /// The most common three quarters of words
+    Normal,
+    /// Any word on the list
+    Hard,
+}
+
+impl Difficulty {
+    /// The percentage of words, most common first, that can be the answer
+    fn percentile(self) -> usize {
+        match self {
+            Difficulty::Easy => 25,
+            Difficulty::Normal => 75,
+            Difficulty::Hard => 100,
+        }
+    }
+
+    /// The words that can be the answer at this difficulty. Words without a
+    /// count are taken to be the least common, and if none have one, any word
+    /// can be the answer
+    fn answers<'a>(self, entries: &[(&'a str, Option<u64>)]) -> Vec<&'a str> {
+        if entries.iter().all(|(_, count)| count.is_none()) {
+            return entries.iter().map(|(word, _)| *word).collect();
+        }
+        let mut by_commonness = entries.to_vec();
+        by_commonness.sort_by_key(|(_, count)| std::cmp::Reverse(count.unwrap_or(0)));
+        let keep = (by_commonness.len() * self.percentile())
+            .div_ceil(100)
+            .max(1);
+        by_commonness
+            .into_iter()
+            .take(keep)
+            .map(|(word, _)| word)
+            .collect()
+    }
+}
+
+/// Prints a QWERTY keyboard with each letter coloured by the best clue it's
+/// had, and letters that haven't been guessed left plain
+fn print_keyboard(keyboard: &HashMap<char, Character>, palette: Palette) {
+    for (indent, row) in ["qwertyuiop", "asdfghjkl", "zxcvbnm"].iter().enumerate() {
+        let keys: Vec<String> = row
+            .chars()
+            .map(|c| match keyboard.get(&c) {
+                Some(tile) => tile.paint(palette).to_string(),
+                None => c.to_string(),
+            })
+            .collect();
+        println!("{}{}", " ".repeat(indent), keys.join(" "));
+    }
+}
+
+/// A guess's colours as a row of squares
+fn emoji_row(tiles: &[Character], palette: Palette) -> String {
+    let (green, yellow) = palette.squares();
+    tiles
+        .iter()
+        .map(|t| match t {
+            Character::Green(_) => green,
+            Character::Yellow(_) => yellow,
+            Character::Red(_) => '⬛',
+        })
+        .collect()
+}
+
+/// Builds a spoiler-free summary of the game to share, with a row of squares
+/// for each guess
+fn share_grid(history: &[Vec<Character>], win: bool, palette: Palette) -> String {
+    let mut grid = format!(
+        "Wordle {}/{}",
+        if win {
+            history.len().to_string()
+        } else {
+            "X".to_string()
+        },
+        MAX_GUESSES
+    );
+    for tiles in history {
+        grid.push('\n');
+        grid.push_str(&emoji_row(tiles, palette));
+    }
+    grid
+}
+
 enum ProcessInputError {
     InvalidLength,
+    NotInWordList,
 }
 
-/// Checks the word against the input and returns true if the word is guessed
-/// correctly We also print the word, with some formatting
-fn process_input(word: &str, input: String) -> Result<bool, ProcessInputError> {
+/// Has `host` answer the input with the colour of each letter. We also print
+/// the word, with some formatting. Guesses that aren't in `dictionary` are
+/// rejected. If `hide_yellow` is set, letters in the wrong position are shown
+/// as if they weren't in the word. Tiles are coloured from `palette`
+fn process_input(
+    host: &mut Host,
+    input: &str,
+    dictionary: &HashSet<&str>,
+    hide_yellow: bool,
+    palette: Palette,
+) -> Result<Vec<Character>, ProcessInputError> {
     if input == "exit" {
-        println!("Exiting. The word was {}!", word.blue());
+        println!("Exiting. The word was {}!", host.word().blue());
         std::process::exit(0);
     }
-    if input.len() != word.len() {
+    // counted in letters rather than bytes, so an accented guess is just the
+    // wrong word rather than the wrong length
+    if input.chars().count() != host.word().chars().count() {
         return Err(ProcessInputError::InvalidLength);
     }
-    let mut guessed = String::new();
-    let mut correct = 0;
-    for (i, c) in input.chars().enumerate() {
-        // right letter, right position
-        if word.contains(c) {
-            if word.chars().nth(i).unwrap() == c {
-                guessed.push_str(&format!("{}", c.to_string().green()));
-                correct += 1;
-            } else {
-                // right letter, wrong position
-                guessed.push_str(&format!("{}", c.to_string().yellow()));
-            }
-        // wrong letter
-        } else {
-            guessed.push_str(&format!(
-                "{}",
-                input.chars().nth(i).unwrap().to_string().red()
-            ));
+    if !dictionary.contains(input) {
+        return Err(ProcessInputError::NotInWordList);
+    }
+    let tiles = host.answer(input, hide_yellow);
+
+    println!(
+        "\n{}",
+        tiles
+            .iter()
+            .map(|t| t.paint(palette).to_string())
+            .collect::<String>()
+    );
+    Ok(tiles)
+}
+
+/// The one or two words in `dictionary` fewest edits away from `input`, as long
+/// as they're no more than `MAX_SUGGESTION_DISTANCE` away
+fn closest_words<'a>(input: &str, dictionary: &HashSet<&'a str>) -> Vec<&'a str> {
+    let mut close: Vec<(usize, &str)> = dictionary
+        .iter()
+        .map(|word| (edit_distance(input, word), *word))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .collect();
+    // alphabetically between words as close as each other, so the same typo
+    // always gets the same suggestions
+    close.sort();
+    close.into_iter().take(2).map(|(_, word)| word).collect()
+}
+
+/// The Levenshtein distance between two words: how many letters have to be
+/// added, removed or changed to turn one into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // the distances from a prefix of `a` to each prefix of `b`, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
         }
     }
+    row[b.len()]
+}
+
+/// The colour of each letter of `input` when the word is `word`, by the same
+/// rules as the solver's `calculate_guess_result`: greens claim their copies of
+/// a letter first, then yellows go left to right while the word has copies left
+/// over, and any more are red. The solver only plays 5 letters, so the game
+/// keeps its own copy for any length
+fn score_guess(word: &str, input: &str, hide_yellow: bool) -> Vec<Character> {
+    // first pass: greens, keeping the word's letters that are left unmatched
+    let mut greens = vec![];
+    let mut unmatched = vec![];
+    for (c, answer) in input.chars().zip(word.chars()) {
+        greens.push(c == answer);
+        unmatched.push((c != answer).then_some(answer));
+    }
 
-    println!("\n{}", guessed);
-    Ok(correct == word.len())
+    // second pass: yellows while unmatched copies remain, red otherwise
+    input
+        .chars()
+        .zip(greens)
+        .map(|(c, green)| {
+            if green {
+                return Character::Green(c);
+            }
+            match unmatched.iter_mut().find(|answer| **answer == Some(c)) {
+                Some(copy) => {
+                    *copy = None;
+                    if hide_yellow {
+                        // right letter, wrong position, but we don't let on
+                        Character::Red(c)
+                    } else {
+                        Character::Yellow(c)
+                    }
+                }
+                None => Character::Red(c),
+            }
+        })
+        .collect()
+}
+
+/// The colours of a guess as one digit per tile, red 0, yellow 1 and green 2,
+/// so guesses can be grouped by the clue they got
+fn pattern_code(tiles: &[Character]) -> Vec<u8> {
+    tiles.iter().map(|t| t.rank()).collect()
 }
 
 /// Reads a line from stdin and returns it as a String
@@ -88,6 +814,92 @@ fn read_line() -> String {
     print!(">> ");
     std::io::stdout().flush().unwrap();
     let mut input = String::new();
-    std::io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_string().to_lowercase()
+    // once stdin has closed, or gives something that isn't text, no more
+    // guesses are coming
+    match std::io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => "exit".to_string(),
+        Ok(_) => input.trim().to_string().to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(tiles: &[Character]) -> String {
+        tiles
+            .iter()
+            .map(|t| match t {
+                Character::Green(_) => 'G',
+                Character::Yellow(_) => 'Y',
+                Character::Red(_) => '-',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repeated_letters_are_only_yellow_once_per_copy() {
+        // crane has one e, so only the first of speed's is yellow
+        assert_eq!(pattern(&score_guess("crane", "speed", false)), "--Y--");
+        assert_eq!(pattern(&score_guess("abide", "speed", false)), "--Y-Y");
+        // three has two e's, one claimed by the green, so only one more is yellow
+        assert_eq!(pattern(&score_guess("three", "eerie", false)), "Y-G-G");
+    }
+
+    #[test]
+    fn clues_match_the_solver() {
+        let words = [
+            "crane", "speed", "abide", "eerie", "three", "geese", "kebab", "abbey", "llama",
+        ];
+        for answer in words {
+            for guess in words {
+                let solved =
+                    solver::calculate_guess_result(&answer.to_string(), &guess.to_string());
+                assert_eq!(
+                    pattern(&score_guess(answer, guess, false)),
+                    solved.pattern(),
+                    "{} against {}",
+                    guess,
+                    answer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn absurdle_groups_by_the_real_clue() {
+        // against speed, crane and trace get the same clue and abide another, so
+        // the host keeps crane and trace
+        let mut host = Host::Absurdle(vec!["abide", "crane", "trace"]);
+        let tiles = host.answer("speed", false);
+        assert_eq!(pattern(&tiles), "--Y--");
+        assert!(matches!(host, Host::Absurdle(ref kept) if kept == &["crane", "trace"]));
+    }
+
+    #[test]
+    fn hidden_yellows_are_drawn_like_letters_not_in_the_word() {
+        colored::control::set_override(true);
+        // every letter of trace but t is in crane, and c is out of place
+        let tiles = score_guess("crane", "trace", true);
+        assert_eq!(pattern(&tiles), "-GG-G");
+        assert_eq!(
+            tiles[3].paint(Palette::Standard).fgcolor,
+            Some(colored::Color::Red)
+        );
+        // the same guess shows its yellows otherwise
+        assert_eq!(pattern(&score_guess("crane", "trace", false)), "-GGYG");
+    }
+
+    #[test]
+    fn a_daily_word_only_counts_the_first_time_its_played() {
+        let mut stats = Stats::default();
+        assert!(stats.record(Some("2024-02-29"), Some(3)));
+        assert!(!stats.record(Some("2024-02-29"), None));
+        assert!(stats.record(Some("2024-03-01"), None));
+        assert!(stats.record(None, Some(4)));
+        assert!(stats.record(None, Some(4)));
+        assert_eq!(stats.played, 4);
+        assert_eq!(stats.wins, 3);
+        assert_eq!(stats.guesses[2], 1);
+    }
 }